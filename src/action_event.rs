@@ -0,0 +1,27 @@
+use crate::{Event, EventCtx, EventResult, TickTime};
+use std::marker::PhantomData;
+
+// wraps a one-shot closure so it can sit in the queue like any other event
+pub(crate) struct ActionEvent<W, F> {
+    action: Option<F>,
+    _world: PhantomData<fn(&mut W)>,
+}
+
+impl<W, F: FnOnce(&mut W)> ActionEvent<W, F> {
+    pub(crate) fn new(action: F) -> Self {
+        Self {
+            action: Some(action),
+            _world: PhantomData,
+        }
+    }
+}
+
+impl<W: 'static, T: TickTime, F: FnOnce(&mut W) + 'static> Event<W, T> for ActionEvent<W, F> {
+    fn execute(&mut self, world: &mut W, _ctx: &mut EventCtx<W, T>) -> EventResult {
+        if let Some(action) = self.action.take() {
+            action(world);
+        }
+
+        EventResult::Continue
+    }
+}
@@ -0,0 +1,8 @@
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BenchmarkResult {
+    pub ticks_per_second: f64,
+    pub events_per_second: f64,
+    pub avg_step_ns: u64,
+    pub max_step_ns: u64,
+    pub min_step_ns: u64,
+}
@@ -0,0 +1,112 @@
+use crate::{Event, EventCtx, EventResult, TickTime};
+
+// runs a batch of same-tick events back to back as a single queue entry
+pub(crate) struct ChainEvent<W, T: TickTime = u64> {
+    events: Vec<Box<dyn Event<W, T>>>,
+}
+
+impl<W, T: TickTime> ChainEvent<W, T> {
+    pub(crate) fn new(events: Vec<Box<dyn Event<W, T>>>) -> Self {
+        Self { events }
+    }
+}
+
+impl<W: 'static, T: TickTime> Event<W, T> for ChainEvent<W, T> {
+    fn execute(&mut self, world: &mut W, ctx: &mut EventCtx<W, T>) -> EventResult {
+        // a halt finishes the batch first, since the rest of it has nowhere to wait. the batch
+        // shares one ctx, so each event's reschedule and failure are taken out as soon as it returns
+        let mut result = EventResult::Continue;
+        let mut failure = None;
+        for mut event in std::mem::take(&mut self.events) {
+            if event.execute(world, ctx) == EventResult::Halt {
+                result = EventResult::Halt;
+            }
+            let failed = ctx.failure.take();
+            failure = failure.or(failed);
+
+            // only the batch itself has a queue entry, so the event leaves it for one of its own
+            if let Some(delay) = ctx.reschedule.take() {
+                ctx.scheduler().schedule(event, delay);
+            }
+        }
+        ctx.failure = failure;
+
+        result
+    }
+
+    fn on_cancel(&self, world: &mut W, current_tick: T) {
+        for event in &self.events {
+            event.on_cancel(world, current_tick);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Engine, Event, EventCtx, EventResult, Fallible, FallibleEvent, StepError};
+    use std::io;
+
+    struct Log(&'static str);
+
+    impl Event<Vec<String>> for Log {
+        fn execute(&mut self, log: &mut Vec<String>, ctx: &mut EventCtx<Vec<String>>) -> EventResult {
+            log.push(format!("{}: {}", ctx.tick(), self.0));
+            EventResult::Continue
+        }
+    }
+
+    struct Twice {
+        runs: u32,
+    }
+
+    impl Event<Vec<String>> for Twice {
+        fn execute(&mut self, log: &mut Vec<String>, ctx: &mut EventCtx<Vec<String>>) -> EventResult {
+            self.runs += 1;
+            log.push(format!("{}: twice {}", ctx.tick(), self.runs));
+            if self.runs < 2 {
+                ctx.reschedule_self(3);
+            }
+
+            EventResult::Continue
+        }
+    }
+
+    struct Fail(&'static str);
+
+    impl FallibleEvent<Vec<String>> for Fail {
+        type Error = io::Error;
+
+        fn try_execute(&mut self, _log: &mut Vec<String>, _ctx: &mut EventCtx<Vec<String>>) -> io::Result<()> {
+            Err(io::Error::other(self.0))
+        }
+    }
+
+    #[test]
+    fn test_reschedule_self_only_requeues_that_event() {
+        let mut log = Vec::new();
+        let mut engine = Engine::build();
+        engine.schedule(Box::new(Log("a")), 1);
+        engine.schedule(Box::new(Twice { runs: 0 }), 1);
+        engine.schedule(Box::new(Log("b")), 1);
+        assert_eq!(engine.coalesce_duplicate_ticks(), 1);
+
+        engine.step_until(10, &mut log);
+        assert_eq!(log, vec!["1: a", "1: twice 1", "1: b", "4: twice 2"]);
+    }
+
+    #[test]
+    fn test_first_failure_in_a_batch_is_kept() {
+        let mut log = Vec::new();
+        let mut engine = Engine::build();
+        engine.schedule(Box::new(Fallible::new(Fail("first"))), 1);
+        engine.schedule(Box::new(Log("a")), 1);
+        engine.schedule(Box::new(Fallible::new(Fail("second"))), 1);
+        assert_eq!(engine.coalesce_duplicate_ticks(), 1);
+
+        let Err(StepError::Event { tick, error, .. }) = engine.step_fallible(&mut log) else {
+            panic!("expected the batch to fail");
+        };
+        assert_eq!((tick, error.to_string()), (1, "first".to_string()));
+        assert_eq!(log, vec!["1: a"]);
+    }
+}
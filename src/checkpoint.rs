@@ -0,0 +1,7 @@
+use crate::Engine;
+
+/// saves progress for `Engine::step_then_checkpoint`, which checkpoints every so many whole
+/// ticks and so only exists for the default `u64` time
+pub trait Checkpointer<W> {
+    fn checkpoint(&mut self, tick: u64, world: &W, engine: &Engine<W>);
+}
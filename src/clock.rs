@@ -0,0 +1,125 @@
+use crate::TickTime;
+use std::time::Duration;
+
+/// drives how `current_tick` moves each step. the engine treats a time earlier than the
+/// previous one as an overflow and applies its `OverflowPolicy`
+pub trait SimulationClock<T: TickTime = u64> {
+    fn current_time(&self) -> T;
+    fn advance(&mut self);
+    /// repositions the clock, used by the engine to hold or restore time on overflow
+    fn set_time(&mut self, time: T);
+    /// the simulated duration the current time represents, if the clock has one
+    fn to_duration(&self) -> Option<Duration>;
+}
+
+/// the default clock: starts at 0 and moves forward one tick per step
+pub struct MonotonicClock<T = u64> {
+    tick: T,
+    stride: T,
+}
+
+impl MonotonicClock {
+    pub fn new() -> Self {
+        Self::with_stride(0, 1)
+    }
+
+    /// starts at `start` and moves forward `stride` ticks per step
+    pub fn with_stride(start: u64, stride: u64) -> Self {
+        Self {
+            tick: start,
+            stride,
+        }
+    }
+}
+
+impl<T: TickTime> MonotonicClock<T> {
+    /// `with_stride` for any `TickTime`, e.g. `f64` seconds moving `step` per step
+    pub fn with_step(start: T, step: T) -> Self {
+        Self { tick: start, stride: step }
+    }
+}
+
+impl Default for MonotonicClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: TickTime> SimulationClock<T> for MonotonicClock<T> {
+    fn current_time(&self) -> T {
+        self.tick
+    }
+
+    fn advance(&mut self) {
+        self.tick = self.tick.wrapping_add(self.stride);
+    }
+
+    fn set_time(&mut self, time: T) {
+        self.tick = time;
+    }
+
+    fn to_duration(&self) -> Option<Duration> {
+        None
+    }
+}
+
+/// what `step` does when the next tick would not fit in a `u64`, or past `TickTime::MAX`. the
+/// same goes for scheduling or rescheduling past it: `Panic` panics in `schedule` and the error
+/// variants of `try_schedule` and `Engine::reschedule` report it under `Panic` and `Error` alike
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OverflowPolicy {
+    #[default]
+    Panic,
+    /// stay at `TickTime::MAX`; events due at that tick keep firing
+    Saturate,
+    /// carry on from the wrapped-around value
+    Wrap,
+    /// leave the tick unchanged and return `SimulationError::TickOverflow` from `try_step`
+    Error,
+}
+
+impl OverflowPolicy {
+    // `tick + delay` under this policy, `None` if it overflowed under `Panic` or `Error`
+    pub(crate) fn add_ticks<T: TickTime>(self, tick: T, delay: T) -> Option<T> {
+        match self {
+            OverflowPolicy::Saturate => Some(tick.checked_add(delay).unwrap_or(T::MAX)),
+            OverflowPolicy::Wrap => Some(tick.wrapping_add(delay)),
+            OverflowPolicy::Panic | OverflowPolicy::Error => tick.checked_add(delay),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_monotonic_clock_advances_by_one() {
+        let mut clock = MonotonicClock::new();
+        assert_eq!(clock.current_time(), 0);
+
+        clock.advance();
+        clock.advance();
+
+        assert_eq!(clock.current_time(), 2);
+        assert_eq!(clock.to_duration(), None);
+    }
+
+    #[test]
+    fn test_monotonic_clock_with_stride() {
+        let mut clock = MonotonicClock::with_stride(10, 60);
+
+        clock.advance();
+
+        assert_eq!(clock.current_time(), 70);
+    }
+
+    #[test]
+    fn test_monotonic_clock_wraps_instead_of_panicking() {
+        let mut clock = MonotonicClock::with_stride(u64::MAX, 2);
+
+        clock.advance();
+
+        assert_eq!(clock.current_time(), 1);
+    }
+}
@@ -1,209 +1,4244 @@
-use crate::Event;
+#[cfg(feature = "bench")]
+use crate::BenchmarkResult;
+#[cfg(feature = "serde")]
+use crate::{EngineState, EventRegistry, RegistryError, SavedEvent};
+use crate::action_event::ActionEvent;
+use crate::chain_event::ChainEvent;
+use crate::fn_event::FnEvent;
+use crate::realtime::SoftRealtime;
+use crate::Checkpointer;
+use crate::CloneEvent;
+use crate::{MonotonicClock, OverflowPolicy, SimulationClock};
+use crate::{Event, EventCtx, EventResult};
+use crate::EventHandle;
+use crate::EventId;
+use crate::InspectorPlugin;
+use crate::ExecutionOrder;
+use crate::ExecutionRecord;
+use crate::EventQuota;
+use crate::EngineSnapshot;
+use crate::EngineMetrics;
+use crate::ProgressReporter;
+use crate::QueueSnapshot;
+use crate::RescheduleError;
+use crate::ScheduleError;
+use crate::SimulationError;
+use crate::StepError;
+use crate::TickTime;
+use crate::{RunResult, SimulationReport, StepReport, StopReason, TickStats};
 use crate::Scheduler;
-use crate::scheduled_wrapper::ScheduledEvent;
-use priority_queue::PriorityQueue;
+use crate::event_queue::{EventQueue, QueueKey};
+use crate::scheduled_wrapper::{Repeat, ScheduledEvent};
+use crate::snapshot::SnapshotEntry;
+#[cfg(feature = "serde")]
+use crate::state::StateEntry;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::any::{Any, TypeId};
 use std::cmp::Reverse;
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+use std::collections::hash_map::DefaultHasher;
+use std::fmt;
+use std::error::Error;
+use std::hash::{Hash, Hasher};
+use std::io::{self, Write};
+use std::time::{Duration, Instant};
 
-pub struct Engine<W> {
-    current_tick: u64,
+type TickHook<W, T> = Box<dyn FnMut(&mut W, T)>;
+
+pub struct Engine<W, T: TickTime = u64> {
+    current_tick: T,
     total_events_executed: u64,
+    total_events_deferred: u64,
+    total_events_scheduled: u64,
+    quota_deferrals: u64,
     id_counter: u64,
+    id_step: u64,
+    last_executed_id: Option<EventId>,
+    last_tick_stats: TickStats<T>,
 
-    queue: PriorityQueue<ScheduledEvent<W>, Reverse<(u64, u64)>>,
+    queue: EventQueue<W, T>,
     max_executions_per_tick: u64,
+
+    audit_log: VecDeque<ExecutionRecord<T>>,
+    audit_log_capacity: usize,
+    event_log: Vec<ExecutionRecord<T>>,
+    event_log_enabled: bool,
+    enable_debug_asserts: bool,
+    execution_time: Duration,
+    type_limits: HashMap<TypeId, usize>,
+    clock: Box<dyn SimulationClock<T>>,
+    rng: StdRng,
+    realtime: Option<SoftRealtime>,
+    overflow_policy: OverflowPolicy,
+    execution_order: ExecutionOrder,
+    order_rng: StdRng,
+    plugins: Vec<Box<dyn InspectorPlugin<W, T>>>,
+    quota: EventQuota,
+    quota_used: HashMap<&'static str, usize>,
+    quota_tick: T,
+    recent_tick_counts: VecDeque<u64>,
+    recent_tick_capacity: usize,
+    executed_at_tick_end: u64,
+    ticks_stepped: u64,
+    ticks_with_events: u64,
+    backoff: Option<Box<dyn Fn(usize) -> T>>,
+    // only set while `step_until_all_types_executed` is watching
+    executed_types: Option<HashSet<TypeId>>,
+    // only set while `drain_tick` is collecting
+    drained_ids: Option<Vec<EventId>>,
+    wall_clock_deadline: Option<Instant>,
+    halted: bool,
+    before_execute: Option<Box<dyn FnMut(T, EventId)>>,
+    after_execute: Option<Box<dyn FnMut(T, EventId)>>,
+    tick_start_hooks: Vec<TickHook<W, T>>,
+    tick_end_hooks: Vec<TickHook<W, T>>,
+    // executions per `Event::type_name`, only tracked after `enable_metrics`
+    executed_by_type: Option<HashMap<&'static str, u64>>,
+    // the `Fallible` event that halted the engine, with its tick and error, until `step_fallible`
+    // reports it or `resume` drops it
+    failure: Option<(EventId, T, Box<dyn Error>)>,
+    // ticks per step as set by `with_tick_stride`, so `time_jump` knows where the next step lands
+    tick_stride: u64,
 }
 
-impl<W> Engine<W> {
-    pub fn initial_event_pool(mut self, initial_pool: Vec<(Box<dyn Event<W>>, u64)>) -> Self {
+impl<W: 'static, T: TickTime> Engine<W, T> {
+    pub fn initial_event_pool(mut self, initial_pool: Vec<(Box<dyn Event<W, T>>, T)>) -> Self {
         for (event, delay) in initial_pool {
             self.schedule(event, delay);
         }
         self
     }
 
+    /// caps executions per tick. events left over stay queued under their original tick and
+    /// priority, so next tick they run before anything scheduled for it, even higher priorities
     pub fn max_executions_per_tick(mut self, execution_rate: u64) -> Self {
         self.max_executions_per_tick = execution_rate;
 
         self
     }
 
-    pub fn build() -> Self {
+    /// keeps the last `capacity` execution records, evicting the oldest first
+    pub fn with_audit_log(mut self, capacity: usize) -> Self {
+        self.audit_log_capacity = capacity;
+        self.audit_log = VecDeque::with_capacity(capacity);
+
+        self
+    }
+
+    /// records every execution in an unbounded log, see `event_log`
+    pub fn with_event_log(mut self) -> Self {
+        self.event_log_enabled = true;
+
+        self
+    }
+
+    /// validates the queue around every tick and rejects duplicate ids on insert.
+    /// the checks only run in builds with `debug_assertions`, release builds skip them
+    pub fn with_debug_assertions(mut self) -> Self {
+        self.enable_debug_asserts = true;
+
+        self
+    }
+
+    /// caps how many events of type `E` may be pending at once; further schedules are rejected
+    pub fn with_max_events_per_type<E: 'static>(mut self, max: usize) -> Self {
+        self.type_limits.insert(TypeId::of::<E>(), max);
+
+        self
+    }
+
+    pub fn with_overflow_policy(mut self, policy: OverflowPolicy) -> Self {
+        self.overflow_policy = policy;
+
+        self
+    }
+
+    /// sets how events due at the same tick are ordered. events already queued are re-ranked
+    pub fn set_execution_order(mut self, order: ExecutionOrder) -> Self {
+        self.execution_order = order;
+        if let ExecutionOrder::Random(seed) = order {
+            self.order_rng = StdRng::seed_from_u64(seed);
+        }
+
+        let mut keys: Vec<QueueKey<T>> = self.queue.iter().map(|(_, Reverse(key))| *key).collect();
+        keys.sort();
+        for key in keys {
+            let Some((item, _)) = self.queue.get(&key.id) else {
+                continue;
+            };
+            let order = self.execution_order.order_key(key.id, &*item.event, &mut self.order_rng);
+            self.queue.change_priority(&key.id, Reverse(QueueKey { order, ..key }));
+        }
+
+        self
+    }
+
+    /// registers `plugin` to observe scheduling, execution, cancellation and tick ends.
+    /// plugins are called in registration order
+    pub fn with_inspector_plugin<P: InspectorPlugin<W, T>>(mut self, plugin: P) -> Self {
+        self.plugins.push(Box::new(plugin));
+
+        self
+    }
+
+    /// limits how many events of each `schedule_tagged` category execute per tick. events over
+    /// their category's quota are deferred to the next tick without using up `max_executions_per_tick`
+    pub fn with_event_quota(mut self, quota: EventQuota) -> Self {
+        self.quota = quota;
+
+        self
+    }
+
+    /// how many of the most recent per-tick execution counts `recent_throughput` can see (1000 by default)
+    pub fn with_throughput_window(mut self, ticks: usize) -> Self {
+        self.recent_tick_capacity = ticks;
+        while self.recent_tick_counts.len() > ticks {
+            self.recent_tick_counts.pop_front();
+        }
+
+        self
+    }
+
+    /// adds `backoff_fn(queue_depth)` ticks to the delay of every event scheduled from now on,
+    /// spreading load once the queue gets deep
+    pub fn with_event_backoff<F: Fn(usize) -> T + 'static>(mut self, backoff_fn: F) -> Self {
+        self.backoff = Some(Box::new(backoff_fn));
+
+        self
+    }
+
+    /// calls `hook(tick, id)` right before every event executes
+    pub fn on_before_execute<F: FnMut(T, EventId) + 'static>(mut self, hook: F) -> Self {
+        self.before_execute = Some(Box::new(hook));
+
+        self
+    }
+
+    /// calls `hook(tick, id)` right after every event executes, before anything it scheduled runs
+    pub fn on_after_execute<F: FnMut(T, EventId) + 'static>(mut self, hook: F) -> Self {
+        self.after_execute = Some(Box::new(hook));
+
+        self
+    }
+
+    /// calls `hook(world, tick)` at the start of every stepped tick, before any event runs.
+    /// hooks run in registration order; ticks skipped by `advance_to_next_event` don't count
+    pub fn on_tick_start<F: FnMut(&mut W, T) + 'static>(mut self, hook: F) -> Self {
+        self.tick_start_hooks.push(Box::new(hook));
+
+        self
+    }
+
+    /// calls `hook(world, tick)` once a stepped tick is over, however it ended: queue empty,
+    /// `max_executions_per_tick` reached or a halt
+    pub fn on_tick_end<F: FnMut(&mut W, T) + 'static>(mut self, hook: F) -> Self {
+        self.tick_end_hooks.push(Box::new(hook));
+
+        self
+    }
+
+    /// starts counting executions per event type for `metrics`. without it nothing is tracked
+    pub fn enable_metrics(mut self) -> Self {
+        self.executed_by_type.get_or_insert_with(HashMap::new);
+
+        self
+    }
+
+    /// refuses to step once `max_wall_seconds` of real time have passed from this call.
+    /// `try_step` then returns `SimulationError::WallClockLimitExceeded`; `step` and the loops
+    /// built on it panic with that error
+    pub fn with_wall_clock_limit(mut self, max_wall_seconds: f64) -> Self {
+        self.wall_clock_deadline = Some(Instant::now() + Duration::from_secs_f64(max_wall_seconds));
+
+        self
+    }
+
+    /// seeds the engine's rng, which `Scheduler::rng` hands to events, so randomised operations
+    /// are reproducible
+    pub fn rng_seed(mut self, seed: u64) -> Self {
+        self.rng = StdRng::seed_from_u64(seed);
+
+        self
+    }
+
+    /// ids continue from `start`, advancing by `step` - the first scheduled event gets `start + step`
+    pub fn with_consistent_id_range(mut self, start: u64, step: u64) -> Self {
+        assert!(step > 0, "id step must be non-zero");
+
+        self.id_counter = start;
+        self.id_step = step;
+
+        self
+    }
+
+    /// builds an engine whose ticks come from `clock` instead of counting up from 0
+    pub fn build_with_clock(clock: Box<dyn SimulationClock<T>>) -> Self {
         Self {
-            current_tick: 0,
+            current_tick: clock.current_time(),
             max_executions_per_tick: 5,
-            queue: PriorityQueue::new(),
+            queue: EventQueue::new(),
             id_counter: 0,
+            id_step: 1,
+            last_executed_id: None,
+            last_tick_stats: TickStats {
+                tick: T::ZERO,
+                events_executed: 0,
+                events_skipped_by_limit: 0,
+                queue_depth_after: 0,
+            },
             total_events_executed: 0,
+            total_events_deferred: 0,
+            total_events_scheduled: 0,
+            quota_deferrals: 0,
+            audit_log: VecDeque::new(),
+            audit_log_capacity: 0,
+            event_log: Vec::new(),
+            event_log_enabled: false,
+            enable_debug_asserts: false,
+            execution_time: Duration::ZERO,
+            type_limits: HashMap::new(),
+            clock,
+            rng: StdRng::from_entropy(),
+            realtime: None,
+            overflow_policy: OverflowPolicy::Panic,
+            execution_order: ExecutionOrder::FifoById,
+            order_rng: StdRng::seed_from_u64(0),
+            plugins: Vec::new(),
+            quota: EventQuota::new(),
+            quota_used: HashMap::new(),
+            quota_tick: T::ZERO,
+            recent_tick_counts: VecDeque::new(),
+            recent_tick_capacity: 1000,
+            executed_at_tick_end: 0,
+            ticks_stepped: 0,
+            ticks_with_events: 0,
+            backoff: None,
+            executed_types: None,
+            drained_ids: None,
+            wall_clock_deadline: None,
+            halted: false,
+            before_execute: None,
+            after_execute: None,
+            tick_start_hooks: Vec::new(),
+            tick_end_hooks: Vec::new(),
+            executed_by_type: None,
+            failure: None,
+            tick_stride: 1,
+        }
+    }
+
+    pub fn schedule(&mut self, event: Box<dyn Event<W, T>>, delay: T) -> EventHandle<W, T> {
+        self.scheduler().schedule(event, delay)
+    }
+
+    /// schedules the closure `f` like `Scheduler::schedule_fn`
+    pub fn schedule_fn<F>(&mut self, f: F, delay: T) -> EventHandle<W, T>
+    where
+        F: FnMut(&mut W, T, &mut Scheduler<'_, W, T>) + 'static,
+    {
+        self.schedule(Box::new(FnEvent::new(f)), delay)
+    }
+
+    /// like `schedule`, but among events due at the same tick lower `priority` fires first
+    pub fn schedule_with_priority(
+        &mut self,
+        event: Box<dyn Event<W, T>>,
+        delay: T,
+        priority: i32,
+    ) -> EventHandle<W, T> {
+        self.scheduler().schedule_with_priority(event, delay, priority)
+    }
+
+    /// schedules `event` `delay` ticks from now and returns its handle, exactly like `schedule`
+    #[deprecated(note = "use schedule")]
+    pub fn schedule_returning_handle(&mut self, event: Box<dyn Event<W, T>>, delay: T) -> EventHandle<W, T> {
+        self.schedule(event, delay)
+    }
+
+    /// schedules every event in `events` for the same tick, returning their ids in input order.
+    /// all or nothing: if any of them is rejected, the ones already queued are cancelled again
+    /// and the first error is returned
+    pub fn schedule_one_of(
+        &mut self,
+        events: Vec<Box<dyn Event<W, T>>>,
+        delay: T,
+    ) -> Result<Vec<EventId>, ScheduleError> {
+        let mut ids = Vec::with_capacity(events.len());
+        for event in events {
+            match self.try_schedule(event, delay) {
+                Ok(id) => ids.push(id),
+                Err(err) => {
+                    for id in ids {
+                        self.cancel(id);
+                    }
+                    return Err(err);
+                }
+            }
+        }
+
+        Ok(ids)
+    }
+
+    pub fn try_schedule(&mut self, event: Box<dyn Event<W, T>>, delay: T) -> Result<EventId, ScheduleError> {
+        self.scheduler().try_schedule(event, delay)
+    }
+
+    /// schedules `event` after `first_delay` ticks and then every `interval` ticks, `repeats`
+    /// times in total or forever if `None`. cancelling the returned handle stops the remaining runs
+    pub fn schedule_repeating(
+        &mut self,
+        event: Box<dyn Event<W, T>>,
+        first_delay: T,
+        interval: T,
+        repeats: Option<u64>,
+    ) -> EventHandle<W, T> {
+        self.scheduler().schedule_repeating(event, first_delay, interval, repeats)
+    }
+
+    /// schedules `event` at the absolute `tick`. ticks at or before `current_tick` have already
+    /// been stepped past and return `ScheduleError::TickInPast`
+    pub fn schedule_at(&mut self, event: Box<dyn Event<W, T>>, tick: T) -> Result<EventHandle<W, T>, ScheduleError> {
+        self.scheduler().schedule_at(event, tick)
+    }
+
+    /// runs `action` against the world at absolute `tick` (or the next step, if that tick has passed)
+    pub fn enqueue_world_action<F: FnOnce(&mut W) + 'static>(&mut self, tick: T, action: F) {
+        let event = Box::new(ActionEvent::new(action));
+        if tick > self.current_tick {
+            self.schedule_at(event, tick).expect("tick is after current_tick");
+        } else {
+            self.schedule(event, T::ZERO);
         }
     }
 
-    pub fn schedule(&mut self, event: Box<dyn Event<W>>, delay: u64) {
-        let mut scheduler = Scheduler {
+    /// like `schedule`, but the event counts against `tag`'s `EventQuota` when it executes
+    pub fn schedule_tagged(
+        &mut self,
+        event: Box<dyn Event<W, T>>,
+        delay: T,
+        tag: &'static str,
+    ) -> EventHandle<W, T> {
+        self.scheduler().schedule_tagged(event, delay, tag)
+    }
+
+    fn scheduler(&mut self) -> Scheduler<'_, W, T> {
+        let debug_asserts = self.debug_asserts_active();
+
+        Scheduler {
             current_tick: self.current_tick,
             queue: &mut self.queue,
             id_counter: &mut self.id_counter,
-        };
+            id_step: self.id_step,
+            debug_asserts,
+            type_limits: &self.type_limits,
+            current_executing_id: None,
+            child_spawn_depth: 0,
+            execution_order: self.execution_order,
+            order_rng: &mut self.order_rng,
+            plugins: &mut self.plugins,
+            backoff: self.backoff.as_deref(),
+            total_events_scheduled: &mut self.total_events_scheduled,
+            halted: &mut self.halted,
+            rng: &mut self.rng,
+            overflow_policy: self.overflow_policy,
+        }
+    }
 
-        scheduler.schedule(event, delay);
+    /// advances one tick and runs what is due. panics if the tick can't advance,
+    /// see `try_step` for the fallible form. does nothing while the engine is halted
+    pub fn step(&mut self, world: &mut W) -> StepReport<T> {
+        self.step_with_mutation(world, |_, _| {})
     }
 
-    pub fn step(&mut self, world: &mut W) {
-        self.current_tick += 1;
+    /// like `step`, but surfaces an `OverflowPolicy::Error` overflow instead of panicking
+    pub fn try_step(&mut self, world: &mut W) -> Result<StepReport<T>, SimulationError> {
+        self.try_step_with_mutation(world, |_, _| {})
+    }
 
-        let mut executions: u64 = 0;
+    /// steps one tick, applying `pre_step_mutation` to the world at the new tick before any event fires
+    pub fn step_with_mutation<F: FnMut(&mut W, T)>(
+        &mut self,
+        world: &mut W,
+        pre_step_mutation: F,
+    ) -> StepReport<T> {
+        match self.try_step_with_mutation(world, pre_step_mutation) {
+            Ok(report) => report,
+            Err(err) => panic!("{}", err),
+        }
+    }
 
-        loop {
-            if executions >= self.max_executions_per_tick {
-                return;
-            }
+    fn try_step_with_mutation<F: FnMut(&mut W, T)>(
+        &mut self,
+        world: &mut W,
+        pre_step_mutation: F,
+    ) -> Result<StepReport<T>, SimulationError> {
+        self.try_step_with_stop(world, None, pre_step_mutation, |_, _| false)
+            .map(|(report, _)| report)
+    }
 
-            let (item, Reverse((time, _))) = match self.queue.pop() {
-                Some(entry) => entry,
-                None => return, // queue is empty
+    // the one path every step goes through. time moves to `jump_to` if given, otherwise by the
+    // clock. `stop` is checked after each execution and ends the tick early once it holds, which
+    // the returned flag reports
+    fn try_step_with_stop<F: FnMut(&mut W, T), S: FnMut(T, &W) -> bool>(
+        &mut self,
+        world: &mut W,
+        jump_to: Option<T>,
+        pre_step_mutation: F,
+        stop: S,
+    ) -> Result<(StepReport<T>, bool), SimulationError> {
+        if self.halted {
+            let report = StepReport {
+                tick: self.current_tick,
+                queue_len_after: self.queue.len(),
+                executed: 0,
+                deferred: 0,
+                halted: true,
             };
+            return Ok((report, false));
+        }
 
-            if time > self.current_tick {
-                self.queue.push(item, Reverse((time, time)));
-                return;
-            }
+        if self.wall_clock_deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+            return Err(SimulationError::WallClockLimitExceeded);
+        }
 
-            let mut scheduler = Scheduler {
-                current_tick: self.current_tick,
-                queue: &mut self.queue,
-                id_counter: &mut self.id_counter,
-            };
+        if self.debug_asserts_active() {
+            self.validate_queue();
+        }
 
-            item.event.execute(world, self.current_tick, &mut scheduler);
-            executions += 1;
-            self.total_events_executed += 1;
+        match jump_to {
+            Some(tick) => self.jump_clock(tick),
+            None => self.advance_clock()?,
         }
+        Ok(self.run_tick(world, pre_step_mutation, stop))
     }
 
-    pub fn step_until(&mut self, target_tick: u64, world: &mut W) {
-        while self.current_tick < target_tick {
-            self.step(world);
+    /// like `try_step`, but also fails when a `Fallible` event returns an error. the tick stops
+    /// at that event and the rest of the queue is left as it is; the engine stays halted until
+    /// `resume`
+    pub fn step_fallible(&mut self, world: &mut W) -> Result<StepReport<T>, StepError<T>> {
+        let report = self.try_step(world).map_err(StepError::Simulation)?;
+        match self.failure.take() {
+            Some((id, tick, error)) => Err(StepError::Event { id, tick, error }),
+            None => Ok(report),
         }
     }
 
-    pub fn get_queue_size(&self) -> usize {
-        self.queue.len()
+    // runs the current tick's events, once time has already moved to it
+    fn run_tick<F: FnMut(&mut W, T), S: FnMut(T, &W) -> bool>(
+        &mut self,
+        world: &mut W,
+        mut pre_step_mutation: F,
+        stop: S,
+    ) -> (StepReport<T>, bool) {
+        self.last_executed_id = None;
+        #[cfg(feature = "tracing")]
+        let _span = tracing::debug_span!("tick", tick = %self.current_tick).entered();
+
+        let executed_before = self.total_events_executed;
+        let quota_deferrals_before = self.quota_deferrals;
+
+        self.start_tick(world);
+        pre_step_mutation(world, self.current_tick);
+        let stopped = self.execute_due(world, self.max_executions_per_tick, stop);
+        self.end_tick(world);
+
+        let executed = self.total_events_executed - executed_before;
+        // anything still due was held back by the cap, which can only happen if it was reached
+        let over_cap = if executed >= self.max_executions_per_tick {
+            self.queue.due_count(self.current_tick) as u64
+        } else {
+            0
+        };
+
+        if self.debug_asserts_active() {
+            self.validate_queue();
+        }
+
+        self.last_tick_stats = TickStats {
+            tick: self.current_tick,
+            events_executed: executed,
+            events_skipped_by_limit: over_cap,
+            queue_depth_after: self.queue.len(),
+        };
+
+        let report = StepReport {
+            tick: self.current_tick,
+            executed,
+            deferred: over_cap + (self.quota_deferrals - quota_deferrals_before),
+            queue_len_after: self.queue.len(),
+            halted: self.halted,
+        };
+
+        (report, stopped)
     }
 
-    pub fn get_total_events_executed(&self) -> u64 {
-        self.total_events_executed
+    /// jumps straight to the tick of the earliest pending event and runs it like `step`, skipping
+    /// the idle ticks in between. returns the new tick, or `None` with nothing queued.
+    /// if the earliest event is already due this is an ordinary `step`. panics where `step`
+    /// would, see `try_advance_to_next_event`
+    pub fn advance_to_next_event(&mut self, world: &mut W) -> Option<T> {
+        match self.try_advance_to_next_event(world) {
+            Ok(tick) => tick,
+            Err(err) => panic!("{}", err),
+        }
     }
 
-    pub fn get_current_tick(&self) -> u64 {
-        self.current_tick
+    /// like `advance_to_next_event`, but surfaces the errors `try_step` does, e.g. a passed
+    /// `with_wall_clock_limit` deadline
+    pub fn try_advance_to_next_event(&mut self, world: &mut W) -> Result<Option<T>, SimulationError> {
+        if self.halted {
+            return Ok(None);
+        }
+        let Some(next) = self.peek_next_tick() else {
+            return Ok(None);
+        };
+
+        let jump_to = (next > self.current_tick).then_some(next);
+        self.try_step_with_stop(world, jump_to, |_, _| {}, |_, _| false)?;
+
+        Ok(Some(self.current_tick))
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    /// calls `try_advance_to_next_event` until the queue is empty and returns the final tick, or
+    /// the first error, which leaves the rest of the queue as it is
+    pub fn run_until_idle(&mut self, world: &mut W) -> Result<T, SimulationError> {
+        while self.try_advance_to_next_event(world)?.is_some() {}
 
-    struct TestWorld {
-        gold: i32,
-        logs: Vec<String>,
+        Ok(self.current_tick)
     }
 
-    struct Miner {
-        amount: i32,
+    /// panics if any queue entry disagrees with the id it is keyed by or was never handed out
+    pub fn validate_queue(&self) {
+        for (item, Reverse(key)) in self.queue.iter() {
+            assert_eq!(
+                item.id, key.id,
+                "event {} is queued with the priority of id {}",
+                item.id, key.id
+            );
+            assert!(
+                item.id.as_u64() <= self.id_counter,
+                "event {} is newer than the id counter {}",
+                item.id,
+                self.id_counter
+            );
+        }
     }
 
-    impl Event<TestWorld> for Miner {
-        fn execute(&self, world: &mut TestWorld, tick: u64, scheduler: &mut Scheduler<TestWorld>) {
-            world.gold += self.amount;
-            world
-                .logs
-                .push(format!("Tick {}: Mined {}", tick, self.amount));
+    fn debug_asserts_active(&self) -> bool {
+        cfg!(debug_assertions) && self.enable_debug_asserts
+    }
 
-            // recur every 5 ticks
-            let next_miner = Box::new(Miner {
-                amount: self.amount,
-            });
-            scheduler.schedule(next_miner, 5);
+    /// like repeated `try_step`, but checks `detection` after every single execution and stops
+    /// mid-tick once it holds. returns the tick it stopped at, or `None` if the queue drained first
+    pub fn step_until_first_of_any<F: Fn(T, &W) -> bool>(
+        &mut self,
+        world: &mut W,
+        detection: F,
+    ) -> Result<Option<T>, SimulationError> {
+        while !self.queue.is_empty() && !self.halted {
+            let (_, detected) = self.try_step_with_stop(world, None, |_, _| {}, &detection)?;
+            if detected {
+                return Ok(Some(self.current_tick));
+            }
         }
+
+        Ok(None)
     }
 
-    struct Explosion {
-        power: i32,
+    /// steps until every type in `type_ids` has executed at least once since the call, returning
+    /// the tick the last of them fired at, or `None` if the queue drained first
+    pub fn step_until_all_types_executed<I>(&mut self, world: &mut W, type_ids: I) -> Option<T>
+    where
+        I: IntoIterator<Item = TypeId>,
+    {
+        let mut remaining: HashSet<TypeId> = type_ids.into_iter().collect();
+        self.executed_types = Some(HashSet::new());
+
+        let fired_at = loop {
+            if remaining.is_empty() {
+                break Some(self.current_tick);
+            }
+            if self.queue.is_empty() || self.halted {
+                break None;
+            }
+
+            self.step(world);
+            if let Some(executed) = self.executed_types.as_mut() {
+                remaining.retain(|type_id| !executed.contains(type_id));
+                executed.clear();
+            }
+        };
+
+        self.executed_types = None;
+        fired_at
     }
 
-    impl Event<TestWorld> for Explosion {
-        fn execute(&self, world: &mut TestWorld, tick: u64, _scheduler: &mut Scheduler<TestWorld>) {
-            world
-                .logs
-                .push(format!("Tick {}: BOOM {}", tick, self.power));
+    /// steps one tick, then keeps running "flush rounds" at that same tick until every event
+    /// held back by `max_executions_per_tick` has executed. returns the number of flush rounds
+    pub fn step_until_tick_empty(&mut self, world: &mut W) -> u64 {
+        self.step(world);
+
+        let mut flush_rounds = 0;
+        while self.has_due_events() && !self.halted {
+            self.execute_due(world, self.max_executions_per_tick, |_, _| false);
+            flush_rounds += 1;
         }
+
+        flush_rounds
     }
 
-    #[test]
-    fn test_simulation_workflow() {
-        let mut world = TestWorld {
-            gold: 0,
-            logs: vec![],
-        };
+    /// executes every event due at the current tick in one go, ignoring `max_executions_per_tick`,
+    /// without advancing time. returns the number executed
+    pub fn step_all_at_current_tick(&mut self, world: &mut W) -> u64 {
+        let executed_before = self.total_events_executed;
+        self.execute_due(world, u64::MAX, |_, _| false);
 
-        let mut engine = Engine::build()
-            .max_executions_per_tick(100)
-            .initial_event_pool(vec![
-                (
-                    Box::new(Miner { amount: 10 }) as Box<dyn Event<TestWorld>>,
-                    1,
-                ),
-                (
-                    Box::new(Explosion { power: 9000 }) as Box<dyn Event<TestWorld>>,
-                    12,
-                ),
-            ]);
+        self.total_events_executed - executed_before
+    }
 
-        // Expected Miner Activations: Tick 1, 6, 11, 16. (Total 4 times)
-        // Expected Explosion: Tick 12.
-        engine.step_until(20, &mut world);
+    // moves time straight to `tick`, which is later than the current one
+    fn jump_clock(&mut self, tick: T) {
+        // skipped ticks are idle ticks as far as `total_ticks_without_events` is concerned
+        self.ticks_stepped += self.current_tick.ticks_between(tick);
+        self.clock.set_time(tick);
+        self.current_tick = tick;
+    }
 
-        assert_eq!(world.gold, 40, "Gold should be 40 after 4 mining cycles");
+    fn advance_clock(&mut self) -> Result<(), SimulationError> {
+        let previous = self.current_tick;
+        self.clock.advance();
+        let next = self.clock.current_time();
 
-        assert_eq!(world.logs[0], "Tick 1: Mined 10");
-        assert_eq!(world.logs[1], "Tick 6: Mined 10");
-        assert_eq!(world.logs[2], "Tick 11: Mined 10");
-        assert_eq!(world.logs[3], "Tick 12: BOOM 9000");
-        assert_eq!(world.logs[4], "Tick 16: Mined 10");
+        if next >= previous {
+            self.current_tick = next;
+            return Ok(());
+        }
 
-        assert_eq!(engine.current_tick, 20);
+        match self.overflow_policy {
+            OverflowPolicy::Panic => panic!("current tick overflowed past {}", previous),
+            OverflowPolicy::Saturate => {
+                self.clock.set_time(T::MAX);
+                self.current_tick = T::MAX;
+            }
+            OverflowPolicy::Wrap => self.current_tick = next,
+            OverflowPolicy::Error => {
+                self.clock.set_time(previous);
+                return Err(SimulationError::TickOverflow);
+            }
+        }
+
+        Ok(())
     }
 
-    #[test]
-    fn test_100k_ticks_with_probabilistic_spawning() {
-        use rand::Rng;
+    fn start_tick(&mut self, world: &mut W) {
+        for hook in self.tick_start_hooks.iter_mut() {
+            hook(world, self.current_tick);
+        }
+    }
 
-        struct CounterWorld {
-            event_count: u64,
+    fn end_tick(&mut self, world: &mut W) {
+        for hook in self.tick_end_hooks.iter_mut() {
+            hook(world, self.current_tick);
         }
 
-        struct SpawningEvent;
+        let executed = self.total_events_executed - self.executed_at_tick_end;
+        self.executed_at_tick_end = self.total_events_executed;
+        self.ticks_stepped += 1;
+        if executed > 0 {
+            self.ticks_with_events += 1;
+        }
+        if self.recent_tick_capacity > 0 {
+            if self.recent_tick_counts.len() == self.recent_tick_capacity {
+                self.recent_tick_counts.pop_front();
+            }
+            self.recent_tick_counts.push_back(executed);
+        }
 
-        impl Event<CounterWorld> for SpawningEvent {
-            fn execute(
-                &self,
-                world: &mut CounterWorld,
-                _tick: u64,
-                scheduler: &mut Scheduler<CounterWorld>,
-            ) {
-                world.event_count += 1;
+        let queue_size = self.queue.len();
+        for plugin in self.plugins.iter_mut() {
+            plugin.on_tick_end(self.current_tick, queue_size);
+        }
+    }
 
-                // 50% chance to spawn 3 more events
-                let mut rng = rand::thread_rng();
-                if rng.gen_bool(0.5) {
-                    for _ in 0..3 {
-                        scheduler.schedule(Box::new(SpawningEvent), 5);
+    fn has_due_events(&self) -> bool {
+        match self.queue.peek() {
+            Some((_, Reverse(key))) => key.tick <= self.current_tick,
+            None => false,
+        }
+    }
+
+    // runs events due at or before the current tick, up to the per-tick cap.
+    // returns true if `stop` held after one of the executions
+    fn execute_due<F: FnMut(T, &W) -> bool>(
+        &mut self,
+        world: &mut W,
+        limit: u64,
+        stop: F,
+    ) -> bool {
+        let started = Instant::now();
+        let stopped = self.run_due_events(world, limit, stop);
+        self.execution_time += started.elapsed();
+
+        stopped
+    }
+
+    fn run_due_events<F: FnMut(T, &W) -> bool>(
+        &mut self,
+        world: &mut W,
+        limit: u64,
+        mut stop: F,
+    ) -> bool {
+        let mut executions: u64 = 0;
+
+        loop {
+            if executions >= limit || self.halted {
+                return false;
+            }
+
+            let (item, priority) = match self.queue.pop() {
+                Some(entry) => entry,
+                None => return false, // queue is empty
+            };
+
+            if priority.0.tick > self.current_tick {
+                self.queue.push(item, priority);
+                self.total_events_deferred += 1;
+                return false;
+            }
+
+            if self.quota_exhausted(&item) {
+                self.quota_deferrals += 1;
+                match self.overflow_policy.add_ticks(self.current_tick, T::ONE) {
+                    Some(next) if next > self.current_tick => {
+                        self.queue.push(item, Reverse(QueueKey { tick: next, ..priority.0 }));
+                        continue;
+                    }
+                    // at the ceiling there is no later tick to defer to; the rest waits for the next step
+                    _ => {
+                        self.queue.push(item, priority);
+                        return false;
                     }
                 }
             }
+
+            self.execute_item(world, item, self.current_tick);
+            executions += 1;
+
+            if stop(self.current_tick, world) {
+                return true;
+            }
+        }
+    }
+
+    // charges a tagged event against its category's quota for the current tick,
+    // returning true if none is left
+    fn quota_exhausted(&mut self, item: &ScheduledEvent<W, T>) -> bool {
+        let Some(tag) = item.tag else {
+            return false;
+        };
+        let Some(limit) = self.quota.limit(tag) else {
+            return false;
+        };
+
+        if self.quota_tick != self.current_tick {
+            self.quota_tick = self.current_tick;
+            self.quota_used.clear();
+        }
+
+        let used = self.quota_used.entry(tag).or_insert(0);
+        if *used >= limit {
+            return true;
+        }
+        *used += 1;
+        false
+    }
+
+    // executes a popped event as though it is `tick`, children are scheduled relative to it
+    fn execute_item(&mut self, world: &mut W, mut item: ScheduledEvent<W, T>, tick: T) {
+        if let Some(types) = self.executed_types.as_mut() {
+            types.insert(item.event_type_id());
+        }
+        if let Some(ids) = self.drained_ids.as_mut() {
+            ids.push(item.id);
+        }
+        if let Some(counts) = self.executed_by_type.as_mut() {
+            *counts.entry(item.event.type_name()).or_insert(0) += 1;
+        }
+
+        if let Some(hook) = self.before_execute.as_mut() {
+            hook(tick, item.id);
+        }
+
+        let mut scheduler = self.scheduler();
+        scheduler.current_tick = tick;
+        scheduler.current_executing_id = Some(item.id);
+        scheduler.child_spawn_depth = item.spawn_depth + 1;
+        let mut ctx = EventCtx::new(tick, item.id, scheduler);
+        let result = item.event.execute(world, &mut ctx);
+        let reschedule = ctx.reschedule.take();
+        if let Some(error) = ctx.failure.take() {
+            self.failure = Some((item.id, tick, error));
+        }
+        if result == EventResult::Halt {
+            self.halted = true;
+        }
+
+        #[cfg(feature = "tracing")]
+        tracing::debug!(%tick, event = item.name(), id = item.id.as_u64(), "executed");
+
+        if let Some(hook) = self.after_execute.as_mut() {
+            hook(tick, item.id);
+        }
+
+        self.total_events_executed += 1;
+        self.last_executed_id = Some(item.id);
+
+        self.record_execution(ExecutionRecord { id: item.id, tick });
+        for plugin in self.plugins.iter_mut() {
+            plugin.on_execute(item.id, tick);
+        }
+
+        item.repeat = item.repeat.and_then(Repeat::next);
+        if let Some(delay) = reschedule.or(item.repeat.map(|repeat| repeat.interval)) {
+            // an overflowing next run panics under `OverflowPolicy::Panic` and is dropped under `Error`
+            match self.overflow_policy.add_ticks(tick, delay) {
+                Some(next) => {
+                    self.scheduler().enqueue(item, next);
+                }
+                None if self.overflow_policy == OverflowPolicy::Panic => {
+                    panic!("event {} can't run again {} ticks after {}: tick overflowed", item.id, delay, tick)
+                }
+                None => {}
+            }
+        }
+    }
+
+    /// executes every event queued for exactly `tick`, as if it were that tick, without moving
+    /// `current_tick`. events they spawn are scheduled relative to `tick`. returns the count executed
+    pub fn fire_all_pending_for_tick(&mut self, tick: T, world: &mut W) -> u64 {
+        let mut keys: Vec<QueueKey<T>> = self
+            .queue
+            .iter()
+            .filter(|(_, Reverse(key))| key.tick == tick)
+            .map(|(_, Reverse(key))| *key)
+            .collect();
+        keys.sort();
+
+        let mut executed = 0;
+        for key in keys {
+            if self.halted {
+                break;
+            }
+            if let Some((item, _)) = self.queue.remove(&key.id) {
+                self.execute_item(world, item, tick);
+                executed += 1;
+            }
+        }
+
+        executed
+    }
+
+    /// like `fire_all_pending_for_tick`, but keeps going until nothing is left at `tick`,
+    /// including events spawned with delay 0 along the way. returns the total flushed
+    pub fn flush_at_tick(&mut self, tick: T, world: &mut W) -> u64 {
+        let mut flushed = 0;
+        loop {
+            let executed = self.fire_all_pending_for_tick(tick, world);
+            if executed == 0 {
+                return flushed;
+            }
+            flushed += executed;
+        }
+    }
+
+    fn step_counting(&mut self, world: &mut W) -> u64 {
+        self.step(world).executed
+    }
+
+    /// steps until the queue is empty and returns the tick the last event fired at (the current
+    /// tick if nothing was queued). never returns if events keep rescheduling themselves, see
+    /// `step_until_empty_bounded`
+    pub fn step_until_empty(&mut self, world: &mut W) -> T {
+        let mut last_fired = self.current_tick;
+        while !self.queue.is_empty() && !self.halted {
+            if self.step_counting(world) > 0 {
+                last_fired = self.current_tick;
+            }
+        }
+
+        last_fired
+    }
+
+    /// like `step_until_empty` but gives up after `max_ticks` steps, returning `None` if events
+    /// are still queued by then or the engine halted first
+    pub fn step_until_empty_bounded(&mut self, world: &mut W, max_ticks: u64) -> Option<T> {
+        let mut last_fired = self.current_tick;
+        for _ in 0..max_ticks {
+            if self.queue.is_empty() || self.halted {
+                break;
+            }
+            if self.step_counting(world) > 0 {
+                last_fired = self.current_tick;
+            }
+        }
+
+        self.queue.is_empty().then_some(last_fired)
+    }
+
+    /// like `step`, but returns the ids of the events that executed, in execution order
+    pub fn drain_tick(&mut self, world: &mut W) -> Vec<EventId> {
+        self.drained_ids = Some(Vec::new());
+        self.step(world);
+
+        self.drained_ids.take().unwrap_or_default()
+    }
+
+    /// steps until `stop(world, tick)` holds after a tick, `max_ticks` ticks have run, the queue
+    /// is empty or an event halts the engine, whichever comes first
+    pub fn run_until<F: FnMut(&W, T) -> bool>(
+        &mut self,
+        world: &mut W,
+        mut stop: F,
+        max_ticks: Option<u64>,
+    ) -> RunResult<T> {
+        let executed_before = self.total_events_executed;
+        let mut ticks_run = 0;
+
+        let reason = loop {
+            if self.halted {
+                break StopReason::Halted;
+            }
+            if max_ticks.is_some_and(|max| ticks_run >= max) {
+                break StopReason::TickLimit;
+            }
+            if self.queue.is_empty() {
+                break StopReason::QueueEmpty;
+            }
+
+            self.step(world);
+            ticks_run += 1;
+            if stop(world, self.current_tick) {
+                break StopReason::Predicate;
+            }
+        };
+
+        RunResult {
+            reason,
+            tick: self.current_tick,
+            ticks_run,
+            events_executed: self.total_events_executed - executed_before,
+        }
+    }
+
+    /// steps until `target_tick`, or until an event halts the engine
+    pub fn step_until(&mut self, target_tick: T, world: &mut W) {
+        while self.current_tick < target_tick && !self.halted {
+            self.step(world);
+        }
+    }
+
+    pub fn get_queue_size(&self) -> usize {
+        self.queue.len()
+    }
+
+    pub fn get_total_events_executed(&self) -> u64 {
+        self.total_events_executed
+    }
+
+    /// events accepted into the queue so far; rejected schedules are not counted
+    pub fn get_total_events_scheduled(&self) -> u64 {
+        self.total_events_scheduled
+    }
+
+    /// executed / scheduled. close to 1.0 when nearly everything scheduled fires, lower when
+    /// events are cancelled or still pending. 0.0 before anything is scheduled
+    pub fn get_scheduling_efficiency(&self) -> f64 {
+        if self.total_events_scheduled == 0 {
+            return 0.0;
+        }
+
+        self.total_events_executed as f64 / self.total_events_scheduled as f64
+    }
+
+    /// how many times a popped event turned out to be in the future and was put back
+    pub fn total_events_deferred(&self) -> u64 {
+        self.total_events_deferred
+    }
+
+    /// average events executed per tick over the last `window_ticks` ticks, or fewer if not that
+    /// many have run yet or `with_throughput_window` keeps less. 0.0 before the first tick
+    pub fn recent_throughput(&self, window_ticks: u64) -> f64 {
+        let window = (window_ticks as usize).min(self.recent_tick_counts.len());
+        if window == 0 {
+            return 0.0;
+        }
+
+        let executed: u64 = self.recent_tick_counts.iter().rev().take(window).sum();
+        executed as f64 / window as f64
+    }
+
+    /// ticks stepped so far in which at least one event executed
+    pub fn total_ticks_with_events(&self) -> u64 {
+        self.ticks_with_events
+    }
+
+    /// ticks stepped so far in which nothing executed. counts steps rather than reading
+    /// `current_tick`, so strided and custom clocks are measured the same way
+    pub fn total_ticks_without_events(&self) -> u64 {
+        self.ticks_stepped - self.ticks_with_events
+    }
+
+    /// executed and pending counts per event type, or `None` unless `enable_metrics` was called.
+    /// pending counts walk the queue
+    pub fn metrics(&self) -> Option<EngineMetrics> {
+        let executed = self.executed_by_type.as_ref()?;
+
+        let mut metrics = EngineMetrics::default();
+        for (&name, &count) in executed {
+            metrics.per_type.entry(name).or_default().executed = count;
+        }
+        for (item, _) in self.queue.iter() {
+            metrics.per_type.entry(item.event.type_name()).or_default().pending += 1;
+        }
+
+        Some(metrics)
+    }
+
+    /// zeroes the execution counts behind `metrics`, if enabled
+    pub fn reset_metrics(&mut self) {
+        if let Some(counts) = self.executed_by_type.as_mut() {
+            counts.clear();
+        }
+    }
+
+    /// true once an event has called `Scheduler::halt`, until `resume`
+    pub fn is_halted(&self) -> bool {
+        self.halted
+    }
+
+    /// clears a halt. events left over at the halting tick are still due; `step_all_at_current_tick`
+    /// runs them in place, otherwise the next `step` runs them first
+    pub fn resume(&mut self) {
+        self.halted = false;
+        self.failure = None;
+    }
+
+    /// tick of the earliest pending event, without popping it or advancing time
+    pub fn peek_next_tick(&self) -> Option<T> {
+        self.queue.peek().map(|(_, Reverse(key))| key.tick)
+    }
+
+    /// id of the event that will fire next, without popping it
+    pub fn peek_next_id(&self) -> Option<EventId> {
+        self.queue.peek().map(|(item, _)| item.id)
+    }
+
+    pub fn get_current_tick(&self) -> T {
+        self.current_tick
+    }
+
+    pub fn clock(&self) -> &dyn SimulationClock<T> {
+        self.clock.as_ref()
+    }
+
+    /// what the most recent tick run by `step` (or anything built on it) did. all zeros before the first
+    pub fn last_tick_stats(&self) -> TickStats<T> {
+        self.last_tick_stats
+    }
+
+    /// id of the last event executed by the most recent `step`, `None` if that tick was empty
+    pub fn get_last_executed_id(&self) -> Option<EventId> {
+        self.last_executed_id
+    }
+
+    /// iterates pending events in heap order, not tick order
+    pub fn future_events_iter(&self) -> impl Iterator<Item = &ScheduledEvent<W, T>> {
+        self.queue.iter().map(|(item, _)| item)
+    }
+
+    /// attaches `key = value` to a pending event, returning false if it is no longer queued
+    pub fn annotate_event(&mut self, id: EventId, key: &str, value: &str) -> bool {
+        match self.queue.get_mut(&id) {
+            Some(item) => {
+                item.metadata.insert(key.to_string(), value.to_string());
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// drops each pending event with chance `probability` using the engine's rng; a non-finite
+    /// `probability` cancels nothing. events are visited in execution order so a seeded engine
+    /// cancels the same ones every run
+    pub fn cancel_events_with_probability(&mut self, probability: f32) -> usize {
+        if !probability.is_finite() {
+            return 0;
+        }
+        let probability = probability.clamp(0.0, 1.0) as f64;
+
+        let mut keys: Vec<QueueKey<T>> = self.queue.iter().map(|(_, Reverse(key))| *key).collect();
+        keys.sort();
+
+        let mut cancelled = 0;
+        for key in keys {
+            if self.rng.gen_bool(probability) && self.queue.remove(&key.id).is_some() {
+                self.notify_cancel(key.id);
+                cancelled += 1;
+            }
+        }
+
+        cancelled
+    }
+
+    /// removes the pending event `id`, returning false if it already fired or never existed
+    pub fn cancel(&mut self, id: EventId) -> bool {
+        self.scheduler().cancel(id)
+    }
+
+    /// moves the pending event `id` to `current_tick + new_delay`, keeping its id and its place
+    /// among events at that tick. the delay is relative, so the new tick can never be in the past;
+    /// a delay of 0 makes it due at the current tick and it runs on the next execution pass
+    pub fn reschedule(&mut self, id: EventId, new_delay: T) -> Result<(), RescheduleError> {
+        self.scheduler().try_reschedule(id, new_delay)
+    }
+
+    /// cancels every pending event whose id is not in `keep_ids`, returning how many were removed
+    pub fn cancel_all_except(&mut self, keep_ids: &HashSet<EventId>) -> usize {
+        self.cancel_where(|item| !keep_ids.contains(&item.id))
+    }
+
+    /// cancels every pending event, e.g. on game over, and returns how many there were. the id
+    /// counter keeps counting so later events never reuse an id. `Event::on_cancel` is not called
+    pub fn clear_queue(&mut self) -> usize {
+        self.cancel_where(|_| true)
+    }
+
+    /// like `clear_queue`, but only for events due at exactly `tick`
+    pub fn clear_queue_at_tick(&mut self, tick: T) -> usize {
+        let doomed: HashSet<EventId> = self.queue.ids_at(tick).collect();
+
+        self.cancel_where(|item| doomed.contains(&item.id))
+    }
+
+    /// cancels every pending event scheduled with `schedule_tagged` under `tag`, e.g. all of an
+    /// entity's actions once it dies. returns the count cancelled
+    pub fn cancel_by_tag(&mut self, tag: &'static str) -> usize {
+        self.cancel_where(|item| item.tag == Some(tag))
+    }
+
+    /// cancels `id` and every pending descendant reachable through `spawned_by`, returning the count.
+    /// only queued events carry `spawned_by`, so the tree is cut wherever an intermediate event
+    /// has already fired
+    pub fn propagate_cancellation(&mut self, id: EventId) -> usize {
+        let doomed = self.pending_descendants(id);
+        self.cancel_where(|item| doomed.contains(&item.id))
+    }
+
+    /// like `propagate_cancellation`, but calls `Event::on_cancel` on each cancelled event,
+    /// in execution order, so they can release world state. returns the count cancelled
+    pub fn cascade_cancel(&mut self, id: EventId, world: &mut W) -> usize {
+        let doomed = self.pending_descendants(id);
+        let mut keys: Vec<QueueKey<T>> = self
+            .queue
+            .iter()
+            .filter(|(item, _)| doomed.contains(&item.id))
+            .map(|(_, Reverse(key))| *key)
+            .collect();
+        keys.sort();
+
+        for key in &keys {
+            if let Some((item, _)) = self.queue.remove(&key.id) {
+                item.event.on_cancel(world, self.current_tick);
+                self.notify_cancel(key.id);
+            }
+        }
+
+        keys.len()
+    }
+
+    // `id` (if pending) plus all pending events in its spawn tree
+    fn pending_descendants(&self, id: EventId) -> HashSet<EventId> {
+        let mut children: HashMap<EventId, Vec<EventId>> = HashMap::new();
+        for (item, _) in self.queue.iter() {
+            if let Some(parent) = item.spawned_by {
+                children.entry(parent).or_default().push(item.id);
+            }
+        }
+
+        let mut found = HashSet::new();
+        if self.queue.contains(&id) {
+            found.insert(id);
+        }
+
+        let mut frontier = vec![id];
+        while let Some(parent) = frontier.pop() {
+            for &child in children.get(&parent).into_iter().flatten() {
+                if found.insert(child) {
+                    frontier.push(child);
+                }
+            }
+        }
+
+        found
+    }
+
+    fn cancel_where<F: FnMut(&ScheduledEvent<W, T>) -> bool>(&mut self, predicate: F) -> usize {
+        let cancelled: Vec<EventId> = self
+            .queue
+            .extract_if(predicate)
+            .into_iter()
+            .map(|(item, _)| item.id)
+            .collect();
+
+        for &id in &cancelled {
+            self.notify_cancel(id);
+        }
+        cancelled.len()
+    }
+
+    fn notify_cancel(&mut self, id: EventId) {
+        for plugin in self.plugins.iter_mut() {
+            plugin.on_cancel(id);
+        }
+    }
+
+    /// the first registered plugin of type `P`
+    pub fn inspector_plugin<P: InspectorPlugin<W, T>>(&self) -> Option<&P> {
+        self.plugins.iter().find_map(|plugin| {
+            let plugin: &dyn Any = &**plugin;
+            plugin.downcast_ref::<P>()
+        })
+    }
+
+    pub fn get_scheduled_tick_for(&self, id: EventId) -> Option<T> {
+        self.queue
+            .get_priority(&id)
+            .map(|Reverse(key)| key.tick)
+    }
+
+    /// wall time the currently queued events should take, extrapolated from the average
+    /// time spent per executed event so far. `None` until at least one event has run
+    pub fn total_simulation_time_estimate(&self) -> Option<Duration> {
+        if self.total_events_executed == 0 {
+            return None;
+        }
+
+        let per_event = self.execution_time.as_secs_f64() / self.total_events_executed as f64;
+        Some(Duration::from_secs_f64(per_event * self.queue.len() as f64))
+    }
+
+    /// replaces every pending `(event, tick)` with `transform(event, tick)`. ids, metadata and
+    /// `spawned_by` carry over; type limits are not re-checked. returns the count transformed
+    pub fn apply_event_transform<F>(&mut self, transform: F) -> usize
+    where
+        F: Fn(Box<dyn Event<W, T>>, T) -> (Box<dyn Event<W, T>>, T),
+    {
+        let entries = self.queue.drain();
+        let transformed = entries.len();
+
+        for (mut item, Reverse(key)) in entries {
+            let (event, tick) = transform(item.event, key.tick);
+            item.event = event;
+            let order = self.execution_order.order_key(item.id, &*item.event, &mut self.order_rng);
+            self.queue.push(item, Reverse(QueueKey { tick, order, ..key }));
+        }
+
+        transformed
+    }
+
+    /// consumes the engine, yielding `(scheduled_tick, event)` in execution order
+    pub fn drain_in_tick_order(self) -> impl Iterator<Item = (T, Box<dyn Event<W, T>>)> {
+        self.queue
+            .into_sorted_iter()
+            .map(|(item, Reverse(key))| (key.tick, item.event))
+    }
+
+    /// how many pending events share `id`'s tick, counting `id` itself
+    pub fn events_at_same_tick_count(&self, id: EventId) -> Option<usize> {
+        let tick = self.get_scheduled_tick_for(id)?;
+
+        Some(self.queue.count_at(tick))
+    }
+
+    /// ids of pending events scheduled while `parent_id` was executing
+    pub fn get_events_spawned_by(&self, parent_id: EventId) -> Vec<EventId> {
+        self.queue
+            .iter()
+            .filter(|(item, _)| item.spawned_by == Some(parent_id))
+            .map(|(item, _)| item.id)
+            .collect()
+    }
+
+    /// `(scheduled_tick, id)` of pending events spawned by `parent_id`, in heap order. O(n)
+    pub fn events_scheduled_by_event(&self, parent_id: EventId) -> Vec<(T, EventId)> {
+        self.queue
+            .iter()
+            .filter(|(item, _)| item.spawned_by == Some(parent_id))
+            .map(|(item, Reverse(key))| (key.tick, item.id))
+            .collect()
+    }
+
+    /// pending event counts keyed by `spawn_depth`. a long tail of deep events points at
+    /// runaway spawning
+    pub fn spawn_depth_histogram(&self) -> HashMap<u32, usize> {
+        let mut histogram = HashMap::new();
+        for (item, _) in self.queue.iter() {
+            *histogram.entry(item.spawn_depth).or_insert(0) += 1;
+        }
+
+        histogram
+    }
+
+    /// pending event counts keyed by tick, in tick order
+    pub fn group_by_tick(&self) -> BTreeMap<T, usize> {
+        self.queue.ticks().map(|tick| (tick, self.queue.count_at(tick))).collect()
+    }
+
+    /// counts pending pairs where the older (lower id) event is scheduled later than the newer one.
+    /// O(n²), meant for diagnostics
+    pub fn event_priority_inversion_count(&self) -> usize {
+        let entries: Vec<(EventId, T)> = self
+            .queue
+            .iter()
+            .map(|(item, Reverse(key))| (item.id, key.tick))
+            .collect();
+
+        let mut inversions = 0;
+        for (i, &(id_a, tick_a)) in entries.iter().enumerate() {
+            for &(id_b, tick_b) in &entries[i + 1..] {
+                if (id_a < id_b && tick_a > tick_b) || (id_b < id_a && tick_b > tick_a) {
+                    inversions += 1;
+                }
+            }
+        }
+
+        inversions
+    }
+
+    pub fn audit_log(&self) -> &VecDeque<ExecutionRecord<T>> {
+        &self.audit_log
+    }
+
+    pub fn event_log(&self) -> &[ExecutionRecord<T>] {
+        &self.event_log
+    }
+
+    /// takes every record accumulated so far, leaving the log empty but still enabled
+    pub fn drain_executed_event_log(&mut self) -> Vec<ExecutionRecord<T>> {
+        std::mem::take(&mut self.event_log)
+    }
+
+    /// panics unless the event log shows `id` executing at `expected_tick`. requires `with_event_log`
+    pub fn assert_event_fired_at(&self, id: EventId, expected_tick: T) {
+        assert!(
+            self.event_log_enabled,
+            "assert_event_fired_at needs the event log, build the engine with `with_event_log()`"
+        );
+
+        let fired: Vec<T> = self
+            .event_log
+            .iter()
+            .filter(|record| record.id == id)
+            .map(|record| record.tick)
+            .collect();
+
+        if fired.contains(&expected_tick) {
+            return;
+        }
+
+        match fired.as_slice() {
+            [] => panic!(
+                "expected event {} to fire at tick {}, but it never fired",
+                id, expected_tick
+            ),
+            ticks => panic!(
+                "expected event {} to fire at tick {}, but it fired at {:?}",
+                id, expected_tick, ticks
+            ),
+        }
+    }
+
+    fn record_execution(&mut self, record: ExecutionRecord<T>) {
+        if self.event_log_enabled {
+            self.event_log.push(record);
+        }
+
+        if self.audit_log_capacity == 0 {
+            return;
+        }
+
+        if self.audit_log.len() == self.audit_log_capacity {
+            self.audit_log.pop_front();
+        }
+        self.audit_log.push_back(record);
+    }
+
+    /// calls `step` exactly `n` times and returns how many events executed across them
+    pub fn step_n(&mut self, n: u64, world: &mut W) -> u64 {
+        (0..n).map(|_| self.step_counting(world)).sum()
+    }
+
+    pub fn run_with_progress<P: ProgressReporter<T>>(
+        &mut self,
+        world: &mut W,
+        target_tick: T,
+        progress: &mut P,
+    ) {
+        while self.current_tick < target_tick && !self.halted {
+            self.step(world);
+            progress.report(self.current_tick, target_tick);
+        }
+    }
+
+    /// the pending `(tick, id)` pairs in the order they will execute, e.g. for an
+    /// "upcoming events" panel. same as iterating `snapshot_queue`
+    pub fn queue_iter(&self) -> impl Iterator<Item = (T, EventId)> {
+        self.snapshot_queue()
+    }
+
+    /// copies the pending `(tick, id)` pairs in the order they will execute
+    pub fn snapshot_queue(&self) -> QueueSnapshot<T> {
+        let mut keys: Vec<QueueKey<T>> = self.queue.iter().map(|(_, Reverse(key))| *key).collect();
+        keys.sort();
+
+        QueueSnapshot::new(keys.into_iter().map(|key| (key.tick, key.id)).collect())
+    }
+
+    /// fingerprint of the current tick and every pending `(tick, id, type_name)` in priority order
+    pub fn tick_sequence_hash(&self) -> u64
+    where
+        T: Hash,
+    {
+        let mut entries: Vec<(T, EventId, &'static str)> = self
+            .queue
+            .iter()
+            .map(|(item, Reverse(key))| (key.tick, item.id, item.event.type_name()))
+            .collect();
+        entries.sort();
+
+        let mut hasher = DefaultHasher::new();
+        self.current_tick.hash(&mut hasher);
+        entries.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    /// steps once and writes `tick N: X events executed, Y queued` to `output`
+    pub fn step_reporting_to(&mut self, world: &mut W, output: &mut dyn Write) -> io::Result<()> {
+        let executed = self.step_counting(world);
+        writeln!(
+            output,
+            "tick {}: {} events executed, {} queued",
+            self.current_tick,
+            executed,
+            self.queue.len()
+        )
+    }
+
+    /// like `step_reporting_to` but writes a `tick,executed,queued` csv row
+    pub fn step_reporting_csv_to(&mut self, world: &mut W, output: &mut dyn Write) -> io::Result<()> {
+        let executed = self.step_counting(world);
+        writeln!(output, "{},{},{}", self.current_tick, executed, self.queue.len())
+    }
+
+    /// steps up to `n` ticks, stopping early once the queue drains, and reports what ran
+    pub fn step_n_ticks_reporting(&mut self, n: u64, world: &mut W) -> SimulationReport {
+        let mut report = SimulationReport::default();
+
+        for _ in 0..n {
+            let executed = self.step_counting(world);
+            report.ticks_advanced += 1;
+            report.total_events_executed += executed;
+            report.events_per_tick.push(executed);
+
+            if self.queue.is_empty() {
+                report.stopped_early = report.ticks_advanced < n;
+                break;
+            }
+        }
+
+        report.final_queue_size = self.queue.len();
+        report
+    }
+
+    /// steps `n` ticks and snapshots the world after each one
+    pub fn step_and_collect_world_states<S>(&mut self, world: &mut W, n: u64) -> Vec<S>
+    where
+        S: Clone + for<'w> From<&'w W>,
+    {
+        let mut trajectory = Vec::with_capacity(n as usize);
+        for _ in 0..n {
+            self.step(world);
+            trajectory.push(S::from(&*world));
+        }
+
+        trajectory
+    }
+
+    /// times `ticks` calls to `step` against `world`
+    #[cfg(feature = "bench")]
+    pub fn benchmark_step(&mut self, world: &mut W, ticks: u64) -> BenchmarkResult {
+        let executed_before = self.total_events_executed;
+        let mut total = Duration::ZERO;
+        let mut max_step = Duration::ZERO;
+        let mut min_step = Duration::MAX;
+
+        for _ in 0..ticks {
+            let started = Instant::now();
+            self.step(world);
+            let elapsed = started.elapsed();
+
+            total += elapsed;
+            max_step = max_step.max(elapsed);
+            min_step = min_step.min(elapsed);
+        }
+
+        if ticks == 0 {
+            min_step = Duration::ZERO;
+        }
+
+        let seconds = total.as_secs_f64();
+        let executed = self.total_events_executed - executed_before;
+        let per_second = |count: u64| {
+            if seconds > 0.0 {
+                count as f64 / seconds
+            } else {
+                0.0
+            }
+        };
+
+        BenchmarkResult {
+            ticks_per_second: per_second(ticks),
+            events_per_second: per_second(executed),
+            avg_step_ns: total.as_nanos().checked_div(ticks as u128).unwrap_or(0) as u64,
+            max_step_ns: max_step.as_nanos() as u64,
+            min_step_ns: min_step.as_nanos() as u64,
+        }
+    }
+
+    /// clones every pending event that opts in through `Event::as_clone_event`, in execution order.
+    /// ticks are absolute; events that cannot be cloned are left out
+    pub fn clone_queue_as_events(&self) -> Vec<(T, Box<dyn CloneEvent<W, T>>)> {
+        let mut entries: Vec<_> = self
+            .queue
+            .iter()
+            .filter_map(|(item, Reverse(key))| {
+                item.event
+                    .as_clone_event()
+                    .map(|event| (*key, event.clone_event()))
+            })
+            .collect();
+        entries.sort_by_key(|(key, _)| *key);
+
+        entries
+            .into_iter()
+            .map(|(key, event)| (key.tick, event))
+            .collect()
+    }
+}
+
+// tick offsets and strides, realtime pacing, checkpoints, engine snapshots and saved state,
+// which only exist for the default `u64` time
+impl<W: 'static> Engine<W> {
+    /// starts the simulation at `tick` instead of 0, e.g. when resuming from a checkpoint.
+    /// events already queued by `initial_event_pool` move with it, keeping their delays
+    pub fn starting_tick(mut self, tick: u64) -> Self {
+        let previous = self.current_tick;
+        let keys: Vec<QueueKey> = self.queue.iter().map(|(_, Reverse(key))| *key).collect();
+        for key in keys {
+            let shifted = key.tick - previous + tick;
+            self.queue.change_priority(&key.id, Reverse(QueueKey { tick: shifted, ..key }));
+        }
+
+        self.clock.set_time(tick);
+        self.current_tick = tick;
+
+        self
+    }
+
+    /// advances `stride` ticks per step; delays are still counted in ticks.
+    /// this swaps in a `MonotonicClock` continuing from the current tick, replacing any custom clock
+    pub fn with_tick_stride(mut self, stride: u64) -> Self {
+        self.clock = Box::new(MonotonicClock::with_stride(self.current_tick, stride));
+        self.tick_stride = stride;
+
+        self
+    }
+
+    /// paces `poll_realtime` at `target_tps` ticks per wall-clock second, running at most
+    /// `max_catchup_ticks` extra ticks per poll when the caller falls behind
+    pub fn with_soft_realtime(mut self, target_tps: f64, max_catchup_ticks: u64) -> Self {
+        self.realtime = Some(SoftRealtime::new(target_tps, max_catchup_ticks));
+
+        self
+    }
+
+    pub fn build() -> Self {
+        Self::build_with_clock(Box::new(MonotonicClock::new()))
+    }
+
+    /// moves `current_tick` to one step before the earliest pending event so the next `step`
+    /// lands on it, and returns how many ticks were skipped. a step is `with_tick_stride` ticks
+    /// (1 by default), so custom clocks moving by anything else should use `advance_to_next_event`.
+    /// unlike `advance_to_next_event` nothing runs, and the skipped ticks are not counted as
+    /// stepped. never moves time backwards; does nothing with an empty queue or when the earliest
+    /// event is already due next step
+    pub fn time_jump(&mut self) -> u64 {
+        let Some(next) = self.peek_next_tick() else {
+            return 0;
+        };
+        if next <= self.current_tick || next - self.current_tick <= self.tick_stride {
+            return 0;
+        }
+
+        let landing = next - self.tick_stride;
+        let skipped = landing - self.current_tick;
+        self.clock.set_time(landing);
+        self.current_tick = landing;
+
+        skipped
+    }
+
+    /// steps once, then checkpoints if the new tick is a multiple of `every_n_ticks` (0 never checkpoints)
+    pub fn step_then_checkpoint<C: Checkpointer<W>>(
+        &mut self,
+        world: &mut W,
+        every_n_ticks: u64,
+        checkpointer: &mut C,
+    ) {
+        self.step(world);
+
+        if every_n_ticks != 0 && self.current_tick.is_multiple_of(every_n_ticks) {
+            checkpointer.checkpoint(self.current_tick, world, self);
+        }
+    }
+
+    /// steps as many ticks as the wall clock allows since the last poll and returns immediately.
+    /// does nothing unless the engine was built `with_soft_realtime`
+    pub fn poll_realtime(&mut self, world: &mut W) -> u64 {
+        let due = match self.realtime.as_mut() {
+            Some(realtime) => realtime.due_ticks(Instant::now()),
+            None => return 0,
+        };
+
+        for _ in 0..due {
+            self.step(world);
+        }
+
+        due
+    }
+
+    /// captures `current_tick`, the id counter, `total_events_executed` and every pending event.
+    /// returns `None` if any pending event does not opt in through `Event::as_clone_event`
+    pub fn snapshot(&self) -> Option<EngineSnapshot<W>> {
+        let entries = self
+            .queue
+            .iter()
+            .map(|(item, Reverse(key))| SnapshotEntry::capture(item, *key))
+            .collect::<Option<Vec<_>>>()?;
+
+        Some(EngineSnapshot {
+            current_tick: self.current_tick,
+            id_counter: self.id_counter,
+            total_events_executed: self.total_events_executed,
+            entries,
+        })
+    }
+
+    /// swaps the queue and counters from `snapshot` back in, dropping whatever is queued now.
+    /// plugins are not notified and other statistics keep running
+    pub fn restore_snapshot(&mut self, snapshot: EngineSnapshot<W>) {
+        let entries = snapshot
+            .entries
+            .into_iter()
+            .map(|entry| {
+                let key = entry.key;
+                (entry.into_scheduled(), key)
+            })
+            .collect();
+
+        self.restore_entries(
+            snapshot.current_tick,
+            snapshot.id_counter,
+            snapshot.total_events_executed,
+            entries,
+        );
+    }
+
+    /// like `snapshot`, but into a serializable `EngineState` holding the pending events as `E`.
+    /// returns `None` if any pending event is some other type
+    #[cfg(feature = "serde")]
+    pub fn to_state<E: Event<W> + Clone>(&self) -> Option<EngineState<E>> {
+        let entries = self
+            .queue
+            .iter()
+            .map(|(item, Reverse(key))| StateEntry::capture(item, *key))
+            .collect::<Option<Vec<_>>>()?;
+
+        Some(EngineState {
+            current_tick: self.current_tick,
+            id_counter: self.id_counter,
+            total_events_executed: self.total_events_executed,
+            entries,
+        })
+    }
+
+    /// like `restore_snapshot`, but from a deserialized `EngineState`
+    #[cfg(feature = "serde")]
+    pub fn restore_state<E: Event<W>>(&mut self, state: EngineState<E>) {
+        let EngineState { current_tick, id_counter, total_events_executed, entries } = state;
+        let entries = entries.into_iter().map(|entry| entry.into_scheduled()).collect();
+
+        self.restore_entries(current_tick, id_counter, total_events_executed, entries);
+    }
+
+    /// like `to_state`, but for queues holding any mix of the event types in `registry`
+    #[cfg(feature = "serde")]
+    pub fn save(&self, registry: &EventRegistry<W>) -> Result<EngineState<SavedEvent>, RegistryError> {
+        let entries = self
+            .queue
+            .iter()
+            .map(|(item, Reverse(key))| {
+                let saved = registry.save(&*item.event)?;
+                Ok(StateEntry::capture_as(item, *key, saved))
+            })
+            .collect::<Result<Vec<_>, RegistryError>>()?;
+
+        Ok(EngineState {
+            current_tick: self.current_tick,
+            id_counter: self.id_counter,
+            total_events_executed: self.total_events_executed,
+            entries,
+        })
+    }
+
+    /// puts back a state from `save`, rebuilding events through `registry`. events keep their ids
+    /// and execution order. on error the engine is left as it was
+    #[cfg(feature = "serde")]
+    pub fn restore(
+        &mut self,
+        state: EngineState<SavedEvent>,
+        registry: &EventRegistry<W>,
+    ) -> Result<(), RegistryError> {
+        let EngineState { current_tick, id_counter, total_events_executed, entries } = state;
+        let entries = entries
+            .into_iter()
+            .map(|entry| entry.try_into_scheduled(|saved| registry.load(saved)))
+            .collect::<Result<Vec<_>, RegistryError>>()?;
+
+        self.restore_entries(current_tick, id_counter, total_events_executed, entries);
+        Ok(())
+    }
+
+    fn restore_entries(
+        &mut self,
+        current_tick: u64,
+        id_counter: u64,
+        total_events_executed: u64,
+        entries: Vec<(ScheduledEvent<W>, QueueKey)>,
+    ) {
+        self.queue.drain();
+        for (item, key) in entries {
+            self.queue.push(item, Reverse(key));
+        }
+
+        self.clock.set_time(current_tick);
+        self.current_tick = current_tick;
+        self.id_counter = id_counter;
+        self.total_events_executed = total_events_executed;
+        self.executed_at_tick_end = total_events_executed;
+    }
+
+    /// merges every tick holding more than one pending event into a single batch entry that runs
+    /// them back to back in their usual order. the batch keeps the id of its first event; the
+    /// others' ids, metadata and tags are dropped, and the whole batch counts as one execution
+    /// against `max_executions_per_tick`. repeating events are left as they are. returns the number
+    /// of ticks coalesced
+    pub fn coalesce_duplicate_ticks(&mut self) -> usize {
+        let mut entries = self.queue.drain();
+        entries.sort_by_key(|(_, Reverse(key))| *key);
+
+        let mut by_tick: Vec<Vec<(ScheduledEvent<W>, Reverse<QueueKey>)>> = Vec::new();
+        for entry in entries {
+            // a repeating event would take the whole batch round with it
+            if entry.0.repeat.is_some() {
+                self.queue.push(entry.0, entry.1);
+                continue;
+            }
+
+            match by_tick.last_mut() {
+                Some(group) if group[0].1.0.tick == entry.1.0.tick => group.push(entry),
+                _ => by_tick.push(vec![entry]),
+            }
+        }
+
+        let mut coalesced = 0;
+        for mut group in by_tick {
+            // each group was filled in execution order
+            let (mut first, key) = group.remove(0);
+            if !group.is_empty() {
+                let mut events = vec![first.event];
+                events.extend(group.into_iter().map(|(item, _)| item.event));
+                first.event = Box::new(ChainEvent::new(events));
+                coalesced += 1;
+            }
+            self.queue.push(first, key);
+        }
+
+        coalesced
+    }
+}
+
+// `W` is never stored, so unlike a derive this needs no `W: Debug`
+impl<W, T: TickTime> fmt::Debug for Engine<W, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Engine")
+            .field("current_tick", &self.current_tick)
+            .field("max_executions_per_tick", &self.max_executions_per_tick)
+            .field("total_events_executed", &self.total_events_executed)
+            .field("queue_len", &self.queue.len())
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{LoggingPlugin, MetricsPlugin, TimelineEntry, TimelinePlugin, TypeMetrics};
+
+    struct TestWorld {
+        gold: i32,
+        logs: Vec<String>,
+    }
+
+    struct Miner {
+        amount: i32,
+    }
+
+    impl Event<TestWorld> for Miner {
+        fn execute(&mut self, world: &mut TestWorld, ctx: &mut EventCtx<TestWorld>) -> EventResult {
+            world.gold += self.amount;
+            world
+                .logs
+                .push(format!("Tick {}: Mined {}", ctx.tick(), self.amount));
+
+            // recur every 5 ticks
+            let next_miner = Box::new(Miner {
+                amount: self.amount,
+            });
+            ctx.scheduler().schedule(next_miner, 5);
+
+            EventResult::Continue
+        }
+    }
+
+    struct GameOver;
+
+    impl Event<TestWorld> for GameOver {
+        fn execute(&mut self, world: &mut TestWorld, ctx: &mut EventCtx<TestWorld>) -> EventResult {
+            world.logs.push(format!("Tick {}: game over", ctx.tick()));
+            ctx.scheduler().halt();
+
+            EventResult::Continue
+        }
+    }
+
+    // pays out and sets off a one-off explosion next tick
+    struct Payday;
+
+    impl Event<TestWorld> for Payday {
+        fn execute(&mut self, world: &mut TestWorld, ctx: &mut EventCtx<TestWorld>) -> EventResult {
+            world.gold += 3;
+            ctx.scheduler().schedule(Box::new(Explosion { power: 1 }), 1);
+
+            EventResult::Continue
+        }
+    }
+
+    #[derive(Clone)]
+    struct Explosion {
+        power: i32,
+    }
+
+    impl Event<TestWorld> for Explosion {
+        fn execute(&mut self, world: &mut TestWorld, ctx: &mut EventCtx<TestWorld>) -> EventResult {
+            world
+                .logs
+                .push(format!("Tick {}: BOOM {}", ctx.tick(), self.power));
+
+            EventResult::Continue
+        }
+
+        fn priority(&self) -> i32 {
+            self.power
+        }
+
+        fn as_clone_event(&self) -> Option<&dyn CloneEvent<TestWorld>> {
+            Some(self)
+        }
+    }
+
+    #[test]
+    fn test_simulation_workflow() {
+        let mut world = TestWorld {
+            gold: 0,
+            logs: vec![],
+        };
+
+        let mut engine = Engine::build()
+            .max_executions_per_tick(100)
+            .initial_event_pool(vec![
+                (
+                    Box::new(Miner { amount: 10 }) as Box<dyn Event<TestWorld>>,
+                    1,
+                ),
+                (
+                    Box::new(Explosion { power: 9000 }) as Box<dyn Event<TestWorld>>,
+                    12,
+                ),
+            ]);
+
+        // Expected Miner Activations: Tick 1, 6, 11, 16. (Total 4 times)
+        // Expected Explosion: Tick 12.
+        engine.step_until(20, &mut world);
+
+        assert_eq!(world.gold, 40, "Gold should be 40 after 4 mining cycles");
+
+        assert_eq!(world.logs[0], "Tick 1: Mined 10");
+        assert_eq!(world.logs[1], "Tick 6: Mined 10");
+        assert_eq!(world.logs[2], "Tick 11: Mined 10");
+        assert_eq!(world.logs[3], "Tick 12: BOOM 9000");
+        assert_eq!(world.logs[4], "Tick 16: Mined 10");
+
+        assert_eq!(engine.current_tick, 20);
+    }
+
+    #[test]
+    fn test_audit_log_keeps_most_recent_records() {
+        let mut world = TestWorld {
+            gold: 0,
+            logs: vec![],
+        };
+
+        let mut engine = Engine::build()
+            .with_audit_log(2)
+            .initial_event_pool(vec![(
+                Box::new(Miner { amount: 1 }) as Box<dyn Event<TestWorld>>,
+                1,
+            )]);
+
+        engine.step_until(20, &mut world);
+
+        // miner fires at ticks 1, 6, 11, 16 - only the last two are retained
+        let ticks: Vec<u64> = engine.audit_log().iter().map(|r| r.tick).collect();
+        assert_eq!(ticks, vec![11, 16]);
+    }
+
+    #[test]
+    fn test_audit_log_disabled_by_default() {
+        let mut world = TestWorld {
+            gold: 0,
+            logs: vec![],
+        };
+
+        let mut engine = Engine::build();
+        engine.schedule(Box::new(Explosion { power: 1 }), 1);
+        engine.step(&mut world);
+
+        assert!(engine.audit_log().is_empty());
+    }
+
+    #[test]
+    fn test_drain_executed_event_log() {
+        let mut world = TestWorld {
+            gold: 0,
+            logs: vec![],
+        };
+        let mut engine = Engine::build()
+            .with_event_log()
+            .initial_event_pool(vec![(
+                Box::new(Miner { amount: 1 }) as Box<dyn Event<TestWorld>>,
+                1,
+            )]);
+
+        engine.step_until(6, &mut world);
+        let drained = engine.drain_executed_event_log();
+        assert_eq!(
+            drained,
+            vec![
+                ExecutionRecord { id: EventId(1), tick: 1 },
+                ExecutionRecord { id: EventId(2), tick: 6 },
+            ]
+        );
+        assert!(engine.event_log().is_empty());
+
+        engine.step_until(11, &mut world);
+        assert_eq!(engine.event_log(), &[ExecutionRecord { id: EventId(3), tick: 11 }]);
+    }
+
+    #[test]
+    fn test_step_until_tick_empty_flushes_capped_tick() {
+        let mut world = TestWorld {
+            gold: 0,
+            logs: vec![],
+        };
+        let mut engine = Engine::build().max_executions_per_tick(2);
+        for power in 0..5 {
+            engine.schedule(Box::new(Explosion { power }), 1);
+        }
+
+        let rounds = engine.step_until_tick_empty(&mut world);
+
+        assert_eq!(rounds, 2);
+        assert_eq!(world.logs.len(), 5);
+        assert_eq!(engine.get_current_tick(), 1);
+        assert_eq!(engine.get_queue_size(), 0);
+    }
+
+    #[test]
+    fn test_debug_assertions_pass_for_normal_run() {
+        let mut world = TestWorld {
+            gold: 0,
+            logs: vec![],
+        };
+        let mut engine = Engine::build()
+            .with_debug_assertions()
+            .initial_event_pool(vec![
+                (
+                    Box::new(Miner { amount: 1 }) as Box<dyn Event<TestWorld>>,
+                    1,
+                ),
+                (
+                    Box::new(Explosion { power: 1 }) as Box<dyn Event<TestWorld>>,
+                    8,
+                ),
+            ]);
+
+        engine.step_until(20, &mut world);
+        engine.validate_queue();
+
+        assert_eq!(world.gold, 4);
+    }
+
+    #[test]
+    fn test_total_simulation_time_estimate() {
+        let mut world = TestWorld {
+            gold: 0,
+            logs: vec![],
+        };
+        let mut engine = Engine::build();
+        engine.schedule(Box::new(Explosion { power: 1 }), 1);
+        engine.schedule(Box::new(Explosion { power: 2 }), 5);
+
+        assert_eq!(engine.total_simulation_time_estimate(), None);
+
+        engine.step(&mut world);
+        assert!(engine.total_simulation_time_estimate().is_some());
+
+        engine.step_until(5, &mut world);
+        assert_eq!(engine.total_simulation_time_estimate(), Some(Duration::ZERO));
+    }
+
+    #[test]
+    fn test_clone_queue_as_events_skips_uncloneable() {
+        let mut engine = Engine::build();
+        engine.schedule(Box::new(Explosion { power: 2 }), 4);
+        engine.schedule(Box::new(Miner { amount: 1 }), 1);
+        engine.schedule(Box::new(Explosion { power: 1 }), 2);
+
+        let cloned = engine.clone_queue_as_events();
+        let ticks: Vec<u64> = cloned.iter().map(|(tick, _)| *tick).collect();
+        assert_eq!(ticks, vec![2, 4]);
+
+        let mut world = TestWorld {
+            gold: 0,
+            logs: vec![],
+        };
+        let mut copy = Engine::build().initial_event_pool(
+            cloned
+                .into_iter()
+                .map(|(tick, event)| (event as Box<dyn Event<TestWorld>>, tick))
+                .collect(),
+        );
+        copy.step_until(5, &mut world);
+
+        assert_eq!(world.logs, vec!["Tick 2: BOOM 1", "Tick 4: BOOM 2"]);
+    }
+
+    #[cfg(feature = "bench")]
+    #[test]
+    fn test_benchmark_step_advances_requested_ticks() {
+        let mut world = TestWorld {
+            gold: 0,
+            logs: vec![],
+        };
+        let mut engine = Engine::build().initial_event_pool(vec![(
+            Box::new(Miner { amount: 1 }) as Box<dyn Event<TestWorld>>,
+            1,
+        )]);
+
+        let result = engine.benchmark_step(&mut world, 50);
+
+        assert_eq!(engine.get_current_tick(), 50);
+        assert!(result.min_step_ns <= result.avg_step_ns);
+        assert!(result.avg_step_ns <= result.max_step_ns);
+        assert!(result.ticks_per_second > 0.0);
+    }
+
+    #[test]
+    fn test_step_then_checkpoint_on_interval() {
+        struct GoldCheckpoints(Vec<(u64, i32, usize)>);
+
+        impl Checkpointer<TestWorld> for GoldCheckpoints {
+            fn checkpoint(&mut self, tick: u64, world: &TestWorld, engine: &Engine<TestWorld>) {
+                self.0.push((tick, world.gold, engine.get_queue_size()));
+            }
+        }
+
+        let mut world = TestWorld {
+            gold: 0,
+            logs: vec![],
+        };
+        let mut engine = Engine::build().initial_event_pool(vec![(
+            Box::new(Miner { amount: 10 }) as Box<dyn Event<TestWorld>>,
+            1,
+        )]);
+        let mut checkpoints = GoldCheckpoints(vec![]);
+
+        for _ in 0..12 {
+            engine.step_then_checkpoint(&mut world, 4, &mut checkpoints);
+        }
+
+        assert_eq!(checkpoints.0, vec![(4, 10, 1), (8, 20, 1), (12, 30, 1)]);
+    }
+
+    #[test]
+    fn test_step_all_at_current_tick_ignores_cap() {
+        let mut world = TestWorld {
+            gold: 0,
+            logs: vec![],
+        };
+        let mut engine = Engine::build().max_executions_per_tick(1);
+        for power in 0..4 {
+            engine.schedule(Box::new(Explosion { power }), 1);
+        }
+        engine.schedule(Box::new(Explosion { power: 9 }), 2);
+
+        engine.step(&mut world);
+        assert_eq!(world.logs.len(), 1);
+
+        assert_eq!(engine.step_all_at_current_tick(&mut world), 3);
+        assert_eq!(world.logs.len(), 4);
+        assert_eq!(engine.get_current_tick(), 1);
+        assert_eq!(engine.get_queue_size(), 1);
+    }
+
+    #[test]
+    fn test_enqueue_world_action_runs_at_absolute_tick() {
+        let mut world = TestWorld {
+            gold: 0,
+            logs: vec![],
+        };
+        let mut engine = Engine::build();
+        engine.step_until(2, &mut world);
+
+        engine.enqueue_world_action(5, |w: &mut TestWorld| w.gold += 7);
+
+        engine.step_until(4, &mut world);
+        assert_eq!(world.gold, 0);
+
+        engine.step(&mut world);
+        assert_eq!(world.gold, 7);
+        assert_eq!(engine.get_queue_size(), 0);
+    }
+
+    #[test]
+    fn test_max_events_per_type_rejects_extra_pending() {
+        let mut world = TestWorld {
+            gold: 0,
+            logs: vec![],
+        };
+        let mut engine = Engine::build()
+            .with_max_events_per_type::<Explosion>(2)
+            .with_max_events_per_type::<Miner>(1);
+
+        assert!(engine.try_schedule(Box::new(Explosion { power: 1 }), 1).is_ok());
+        assert!(engine.try_schedule(Box::new(Explosion { power: 2 }), 2).is_ok());
+        assert_eq!(
+            engine.try_schedule(Box::new(Explosion { power: 3 }), 3),
+            Err(ScheduleError::TypeLimitReached)
+        );
+        assert!(engine.try_schedule(Box::new(Miner { amount: 1 }), 3).is_ok());
+        assert_eq!(engine.get_queue_size(), 3);
+
+        // once one explosion fires there is room again
+        engine.step(&mut world);
+        assert!(engine.try_schedule(Box::new(Explosion { power: 4 }), 1).is_ok());
+    }
+
+    #[test]
+    fn test_step_with_mutation_runs_before_events() {
+        struct Snapshot;
+
+        impl Event<TestWorld> for Snapshot {
+            fn execute(&mut self, world: &mut TestWorld, ctx: &mut EventCtx<TestWorld>) -> EventResult {
+                world.logs.push(format!("Tick {}: gold {}", ctx.tick(), world.gold));
+                EventResult::Continue
+            }
+        }
+
+        let mut world = TestWorld {
+            gold: 0,
+            logs: vec![],
+        };
+        let mut engine = Engine::build();
+        engine.schedule(Box::new(Snapshot), 2);
+
+        engine.step_with_mutation(&mut world, |w, tick| w.gold += tick as i32);
+        engine.step_with_mutation(&mut world, |w, tick| w.gold += tick as i32);
+
+        assert_eq!(world.logs, vec!["Tick 2: gold 3"]);
+    }
+
+    #[test]
+    fn test_drain_in_tick_order() {
+        let mut engine: Engine<TestWorld> = Engine::build();
+        engine.schedule(Box::new(Explosion { power: 1 }), 9);
+        engine.schedule(Box::new(Miner { amount: 1 }), 3);
+        engine.schedule(Box::new(Explosion { power: 2 }), 3);
+
+        let drained: Vec<(u64, &'static str)> = engine
+            .drain_in_tick_order()
+            .map(|(tick, event)| (tick, event.type_name()))
+            .collect();
+
+        assert_eq!(drained.len(), 3);
+        assert_eq!(drained[0].0, 3);
+        assert!(drained[0].1.ends_with("Miner"));
+        assert_eq!(drained[1].0, 3);
+        assert!(drained[1].1.ends_with("Explosion"));
+        assert_eq!(drained[2].0, 9);
+    }
+
+    #[test]
+    fn test_annotate_event() {
+        let mut world = TestWorld {
+            gold: 0,
+            logs: vec![],
+        };
+        let mut engine = Engine::build();
+        engine.schedule(Box::new(Explosion { power: 1 }), 2);
+
+        assert!(engine.annotate_event(EventId(1), "source", "test"));
+        assert!(!engine.annotate_event(EventId(2), "source", "test"));
+
+        let event = engine.future_events_iter().next().unwrap();
+        assert_eq!(event.metadata.get("source").map(String::as_str), Some("test"));
+
+        engine.step_until(2, &mut world);
+        assert!(!engine.annotate_event(EventId(1), "source", "late"));
+    }
+
+    #[test]
+    fn test_step_reporting_to_writes_tick_summaries() {
+        let mut world = TestWorld {
+            gold: 0,
+            logs: vec![],
+        };
+        let mut engine = Engine::build();
+        engine.schedule(Box::new(Explosion { power: 1 }), 1);
+        engine.schedule(Box::new(Explosion { power: 2 }), 1);
+        engine.schedule(Box::new(Explosion { power: 3 }), 3);
+
+        let mut output = Vec::new();
+        engine.step_reporting_to(&mut world, &mut output).unwrap();
+        engine.step_reporting_csv_to(&mut world, &mut output).unwrap();
+
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "tick 1: 2 events executed, 1 queued\n2,0,1\n"
+        );
+    }
+
+    #[test]
+    fn test_build_with_custom_clock() {
+        use std::time::Duration;
+
+        // each step is a minute of simulated time
+        struct MinuteClock {
+            minutes: u64,
+        }
+
+        impl SimulationClock for MinuteClock {
+            fn current_time(&self) -> u64 {
+                self.minutes
+            }
+
+            fn advance(&mut self) {
+                self.minutes += 1;
+            }
+
+            fn set_time(&mut self, time: u64) {
+                self.minutes = time;
+            }
+
+            fn to_duration(&self) -> Option<Duration> {
+                Some(Duration::from_secs(self.minutes * 60))
+            }
+        }
+
+        let mut world = TestWorld {
+            gold: 0,
+            logs: vec![],
+        };
+        let mut engine = Engine::build_with_clock(Box::new(MinuteClock { minutes: 100 }));
+        engine.schedule(Box::new(Explosion { power: 1 }), 2);
+
+        engine.step(&mut world);
+        engine.step(&mut world);
+
+        assert_eq!(world.logs, vec!["Tick 102: BOOM 1"]);
+        assert_eq!(engine.clock().to_duration(), Some(Duration::from_secs(102 * 60)));
+    }
+
+    #[test]
+    fn test_assert_event_fired_at() {
+        let mut world = TestWorld {
+            gold: 0,
+            logs: vec![],
+        };
+        let mut engine = Engine::build().with_event_log();
+        engine.schedule(Box::new(Explosion { power: 1 }), 3);
+        engine.step_until(5, &mut world);
+
+        engine.assert_event_fired_at(EventId(1), 3);
+    }
+
+    #[test]
+    #[should_panic(expected = "expected event 1 to fire at tick 4, but it fired at [3]")]
+    fn test_assert_event_fired_at_reports_actual_tick() {
+        let mut world = TestWorld {
+            gold: 0,
+            logs: vec![],
+        };
+        let mut engine = Engine::build().with_event_log();
+        engine.schedule(Box::new(Explosion { power: 1 }), 3);
+        engine.step_until(5, &mut world);
+
+        engine.assert_event_fired_at(EventId(1), 4);
+    }
+
+    #[test]
+    fn test_tick_stride_advances_multiple_ticks_per_step() {
+        let mut world = TestWorld {
+            gold: 0,
+            logs: vec![],
+        };
+        let mut engine = Engine::build()
+            .with_tick_stride(10)
+            .initial_event_pool(vec![
+                (
+                    Box::new(Explosion { power: 1 }) as Box<dyn Event<TestWorld>>,
+                    5,
+                ),
+                (
+                    Box::new(Explosion { power: 2 }) as Box<dyn Event<TestWorld>>,
+                    20,
+                ),
+            ]);
+
+        engine.step(&mut world);
+        assert_eq!(engine.get_current_tick(), 10);
+        assert_eq!(world.logs, vec!["Tick 10: BOOM 1"]);
+
+        engine.step(&mut world);
+        assert_eq!(world.logs, vec!["Tick 10: BOOM 1", "Tick 20: BOOM 2"]);
+    }
+
+    #[test]
+    fn test_cancel_events_with_probability_is_reproducible() {
+        let build = || {
+            let mut engine: Engine<TestWorld> = Engine::build().rng_seed(7);
+            for power in 0..100 {
+                engine.schedule(Box::new(Explosion { power }), 1 + power as u64 % 10);
+            }
+            engine
+        };
+
+        let mut a = build();
+        let mut b = build();
+        let cancelled = a.cancel_events_with_probability(0.5);
+
+        assert_eq!(cancelled, b.cancel_events_with_probability(0.5));
+        assert_eq!(a.tick_sequence_hash(), b.tick_sequence_hash());
+        assert_eq!(a.get_queue_size(), 100 - cancelled);
+
+        assert_eq!(a.cancel_events_with_probability(0.0), 0);
+        assert_eq!(a.cancel_events_with_probability(1.0), 100 - cancelled);
+        assert_eq!(a.get_queue_size(), 0);
+    }
+
+    #[test]
+    fn test_cancel_events_with_non_finite_probability_cancels_nothing() {
+        let mut engine: Engine<TestWorld> = Engine::build();
+        engine.schedule(Box::new(Explosion { power: 1 }), 1);
+
+        assert_eq!(engine.cancel_events_with_probability(f32::NAN), 0);
+        assert_eq!(engine.cancel_events_with_probability(f32::INFINITY), 0);
+        assert_eq!(engine.get_queue_size(), 1);
+    }
+
+    #[test]
+    fn test_events_at_same_tick_count() {
+        let mut engine: Engine<TestWorld> = Engine::build();
+        engine.schedule(Box::new(Explosion { power: 1 }), 4);
+        engine.schedule(Box::new(Explosion { power: 2 }), 4);
+        engine.schedule(Box::new(Explosion { power: 3 }), 5);
+
+        assert_eq!(engine.events_at_same_tick_count(EventId(1)), Some(2));
+        assert_eq!(engine.events_at_same_tick_count(EventId(3)), Some(1));
+        assert_eq!(engine.events_at_same_tick_count(EventId(4)), None);
+    }
+
+    #[test]
+    fn test_poll_realtime() {
+        let mut world = TestWorld {
+            gold: 0,
+            logs: vec![],
+        };
+
+        let mut idle: Engine<TestWorld> = Engine::build();
+        assert_eq!(idle.poll_realtime(&mut world), 0);
+
+        let mut engine = Engine::build().with_soft_realtime(1000.0, 3);
+        std::thread::sleep(Duration::from_millis(20));
+
+        assert_eq!(engine.poll_realtime(&mut world), 4);
+        assert_eq!(engine.get_current_tick(), 4);
+    }
+
+    #[test]
+    fn test_get_events_spawned_by() {
+        struct Spawner;
+
+        impl Event<TestWorld> for Spawner {
+            fn execute(&mut self, _world: &mut TestWorld, ctx: &mut EventCtx<TestWorld>) -> EventResult {
+                ctx.scheduler().schedule(Box::new(Explosion { power: 1 }), 3);
+                ctx.scheduler().schedule(Box::new(Explosion { power: 2 }), 4);
+
+                EventResult::Continue
+            }
+        }
+
+        let mut world = TestWorld {
+            gold: 0,
+            logs: vec![],
+        };
+        let mut engine = Engine::build();
+        engine.schedule(Box::new(Spawner), 1);
+        engine.schedule(Box::new(Explosion { power: 3 }), 5);
+        engine.step(&mut world);
+
+        let mut children = engine.get_events_spawned_by(EventId(1));
+        children.sort();
+        assert_eq!(children, vec![EventId(3), EventId(4)]);
+        assert!(engine.get_events_spawned_by(EventId(2)).is_empty());
+    }
+
+    #[test]
+    fn test_step_n_ticks_reporting_stops_when_drained() {
+        let mut world = TestWorld {
+            gold: 0,
+            logs: vec![],
+        };
+        let mut engine = Engine::build();
+        engine.schedule(Box::new(Explosion { power: 1 }), 1);
+        engine.schedule(Box::new(Explosion { power: 2 }), 3);
+        engine.schedule(Box::new(Explosion { power: 3 }), 3);
+
+        let report = engine.step_n_ticks_reporting(10, &mut world);
+
+        assert_eq!(
+            report,
+            SimulationReport {
+                ticks_advanced: 3,
+                total_events_executed: 3,
+                events_per_tick: vec![1, 0, 2],
+                final_queue_size: 0,
+                stopped_early: true,
+            }
+        );
+    }
+
+    #[test]
+    fn test_step_n_ticks_reporting_full_run() {
+        let mut world = TestWorld {
+            gold: 0,
+            logs: vec![],
+        };
+        let mut engine = Engine::build().initial_event_pool(vec![(
+            Box::new(Miner { amount: 1 }) as Box<dyn Event<TestWorld>>,
+            1,
+        )]);
+
+        let report = engine.step_n_ticks_reporting(6, &mut world);
+
+        assert_eq!(report.ticks_advanced, 6);
+        assert_eq!(report.events_per_tick, vec![1, 0, 0, 0, 0, 1]);
+        assert_eq!(report.final_queue_size, 1);
+        assert!(!report.stopped_early);
+    }
+
+    #[test]
+    fn test_group_by_tick() {
+        let mut engine: Engine<TestWorld> = Engine::build();
+        engine.schedule(Box::new(Explosion { power: 1 }), 9);
+        engine.schedule(Box::new(Explosion { power: 2 }), 2);
+        engine.schedule(Box::new(Explosion { power: 3 }), 9);
+
+        let groups: Vec<(u64, usize)> = engine.group_by_tick().into_iter().collect();
+
+        assert_eq!(groups, vec![(2, 1), (9, 2)]);
+    }
+
+    #[test]
+    fn test_cancel_all_except() {
+        let mut world = TestWorld {
+            gold: 0,
+            logs: vec![],
+        };
+        let mut engine = Engine::build();
+        for power in 1..=4 {
+            engine.schedule(Box::new(Explosion { power }), power as u64);
+        }
+
+        let removed = engine.cancel_all_except(&HashSet::from([EventId(2), EventId(4), EventId(99)]));
+        assert_eq!(removed, 2);
+
+        engine.step_until(5, &mut world);
+        assert_eq!(world.logs, vec!["Tick 2: BOOM 2", "Tick 4: BOOM 4"]);
+    }
+
+    // spawns one child per generation until `generations` runs out
+    struct Lineage {
+        generations: u32,
+    }
+
+    impl Event<TestWorld> for Lineage {
+        fn execute(&mut self, world: &mut TestWorld, ctx: &mut EventCtx<TestWorld>) -> EventResult {
+            world.logs.push(format!("Tick {}: generation {}", ctx.tick(), self.generations));
+            if self.generations > 0 {
+                ctx.scheduler().schedule(
+                    Box::new(Lineage {
+                        generations: self.generations - 1,
+                    }),
+                    1,
+                );
+                ctx.scheduler().schedule(Box::new(Explosion { power: 0 }), 10);
+            }
+
+            EventResult::Continue
+        }
+    }
+
+    #[test]
+    fn test_propagate_cancellation_cancels_descendants() {
+        let mut world = TestWorld {
+            gold: 0,
+            logs: vec![],
+        };
+        let mut engine = Engine::build();
+        engine.schedule(Box::new(Lineage { generations: 5 }), 1);
+        engine.schedule(Box::new(Explosion { power: 1 }), 20);
+
+        // the root fired and left a lineage child and an explosion behind
+        engine.step(&mut world);
+        assert_eq!(engine.get_queue_size(), 3);
+
+        assert_eq!(engine.propagate_cancellation(EventId(1)), 2);
+        assert_eq!(engine.get_queue_size(), 1);
+        assert_eq!(engine.propagate_cancellation(EventId(1)), 0);
+
+        // a pending event with no children cancels just itself
+        assert_eq!(engine.propagate_cancellation(EventId(2)), 1);
+        assert_eq!(engine.get_queue_size(), 0);
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn test_schedule_returning_handle() {
+        let mut world = TestWorld {
+            gold: 0,
+            logs: vec![],
+        };
+        let mut engine = Engine::build();
+        let handle = engine.schedule_returning_handle(Box::new(Explosion { power: 1 }), 2);
+
+        assert_eq!(handle.id(), EventId(1));
+        assert_eq!(handle.get_tick(&engine), Some(2));
+        assert_eq!(engine.schedule(Box::new(Explosion { power: 2 }), 2).id(), EventId(2));
+
+        engine.step_until(2, &mut world);
+        assert!(!handle.is_pending(&engine));
+    }
+
+    #[test]
+    fn test_step_and_collect_world_states() {
+        #[derive(Clone, Debug, PartialEq)]
+        struct Gold(i32);
+
+        impl From<&TestWorld> for Gold {
+            fn from(world: &TestWorld) -> Self {
+                Gold(world.gold)
+            }
+        }
+
+        let mut world = TestWorld {
+            gold: 0,
+            logs: vec![],
+        };
+        let mut engine = Engine::build().initial_event_pool(vec![(
+            Box::new(Miner { amount: 3 }) as Box<dyn Event<TestWorld>>,
+            2,
+        )]);
+
+        let trajectory: Vec<Gold> = engine.step_and_collect_world_states(&mut world, 7);
+
+        assert_eq!(
+            trajectory,
+            vec![Gold(0), Gold(3), Gold(3), Gold(3), Gold(3), Gold(3), Gold(6)]
+        );
+    }
+
+    fn engine_near_max(policy: OverflowPolicy) -> Engine<TestWorld> {
+        Engine::build_with_clock(Box::new(MonotonicClock::with_stride(u64::MAX - 1, 1)))
+            .with_overflow_policy(policy)
+    }
+
+    #[test]
+    fn test_overflow_policy_saturate() {
+        let mut world = TestWorld {
+            gold: 0,
+            logs: vec![],
+        };
+        let mut engine = engine_near_max(OverflowPolicy::Saturate);
+
+        engine.step(&mut world);
+        engine.step(&mut world);
+
+        assert_eq!(engine.get_current_tick(), u64::MAX);
+        assert_eq!(engine.clock().current_time(), u64::MAX);
+    }
+
+    #[test]
+    fn test_overflow_policy_wrap() {
+        let mut world = TestWorld {
+            gold: 0,
+            logs: vec![],
+        };
+        let mut engine = engine_near_max(OverflowPolicy::Wrap);
+
+        engine.step(&mut world);
+        engine.step(&mut world);
+
+        assert_eq!(engine.get_current_tick(), 0);
+    }
+
+    #[test]
+    fn test_overflow_policy_error() {
+        let mut world = TestWorld {
+            gold: 0,
+            logs: vec![],
+        };
+        let mut engine = engine_near_max(OverflowPolicy::Error);
+
+        assert!(engine.try_step(&mut world).is_ok());
+        assert_eq!(engine.try_step(&mut world), Err(SimulationError::TickOverflow));
+        assert_eq!(engine.get_current_tick(), u64::MAX);
+    }
+
+    #[test]
+    #[should_panic(expected = "current tick overflowed")]
+    fn test_overflow_policy_panic() {
+        let mut world = TestWorld {
+            gold: 0,
+            logs: vec![],
+        };
+        let mut engine = engine_near_max(OverflowPolicy::Panic);
+
+        engine.step(&mut world);
+        engine.step(&mut world);
+    }
+
+    #[test]
+    fn test_scheduling_at_the_ceiling_saturates() {
+        let mut world = TestWorld { gold: 0, logs: vec![] };
+        let mut engine = engine_near_max(OverflowPolicy::Saturate);
+        let bomb = engine.schedule(Box::new(Explosion { power: 1 }), 10);
+        let late = engine.try_schedule(Box::new(Explosion { power: 2 }), u64::MAX).unwrap();
+        assert_eq!(engine.reschedule(bomb.id(), 20), Ok(()));
+
+        assert_eq!(engine.get_scheduled_tick_for(bomb.id()), Some(u64::MAX));
+        assert_eq!(engine.get_scheduled_tick_for(late), Some(u64::MAX));
+        engine.step(&mut world);
+        assert_eq!(world.logs.len(), 2);
+    }
+
+    #[test]
+    fn test_scheduling_at_the_ceiling_wraps() {
+        let mut engine = engine_near_max(OverflowPolicy::Wrap);
+        let bomb = engine.schedule(Box::new(Explosion { power: 1 }), 10);
+        assert_eq!(engine.get_scheduled_tick_for(bomb.id()), Some(8));
+
+        assert_eq!(engine.reschedule(bomb.id(), 3), Ok(()));
+        assert_eq!(engine.get_scheduled_tick_for(bomb.id()), Some(1));
+    }
+
+    #[test]
+    fn test_scheduling_past_the_ceiling_is_an_error() {
+        for policy in [OverflowPolicy::Error, OverflowPolicy::Panic] {
+            let mut engine = engine_near_max(policy);
+            assert_eq!(
+                engine.try_schedule(Box::new(Explosion { power: 1 }), 2),
+                Err(ScheduleError::TickOverflow)
+            );
+
+            let last = engine.schedule(Box::new(Explosion { power: 2 }), 1);
+            assert_eq!(engine.reschedule(last.id(), 2), Err(RescheduleError::TickOverflow));
+            assert_eq!(engine.get_scheduled_tick_for(last.id()), Some(u64::MAX));
+        }
+
+        let mut engine = engine_near_max(OverflowPolicy::Error);
+        let dropped = engine.schedule(Box::new(Explosion { power: 3 }), 2);
+        assert!(!dropped.is_pending(&engine));
+        assert_eq!(engine.get_queue_size(), 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "scheduled tick overflowed")]
+    fn test_scheduling_past_the_ceiling_panics() {
+        let mut engine = engine_near_max(OverflowPolicy::Panic);
+        engine.schedule(Box::new(Explosion { power: 1 }), 2);
+    }
+
+    #[test]
+    fn test_fire_all_pending_for_tick_keeps_clock() {
+        let mut world = TestWorld {
+            gold: 0,
+            logs: vec![],
+        };
+        let mut engine = Engine::build().max_executions_per_tick(1);
+        engine.schedule(Box::new(Explosion { power: 1 }), 50);
+        engine.schedule(Box::new(Explosion { power: 2 }), 50);
+        engine.schedule(Box::new(Miner { amount: 5 }), 50);
+        engine.schedule(Box::new(Explosion { power: 3 }), 60);
+
+        assert_eq!(engine.fire_all_pending_for_tick(50, &mut world), 3);
+
+        assert_eq!(engine.get_current_tick(), 0);
+        assert_eq!(
+            world.logs,
+            vec!["Tick 50: BOOM 1", "Tick 50: BOOM 2", "Tick 50: Mined 5"]
+        );
+        // the miner rescheduled itself relative to the injected tick
+        let ticks: Vec<u64> = engine.group_by_tick().into_keys().collect();
+        assert_eq!(ticks, vec![55, 60]);
+    }
+
+    #[test]
+    fn test_event_priority_inversion_count() {
+        let mut engine: Engine<TestWorld> = Engine::build();
+        engine.schedule(Box::new(Explosion { power: 1 }), 10);
+        engine.schedule(Box::new(Explosion { power: 2 }), 5);
+        engine.schedule(Box::new(Explosion { power: 3 }), 7);
+        engine.schedule(Box::new(Explosion { power: 4 }), 10);
+
+        // (1, 2) and (1, 3) are inverted; equal ticks don't count
+        assert_eq!(engine.event_priority_inversion_count(), 2);
+    }
+
+    fn booms_in_order(order: ExecutionOrder, powers: &[i32]) -> Vec<String> {
+        let mut world = TestWorld { gold: 0, logs: vec![] };
+        let mut engine = Engine::build().max_executions_per_tick(powers.len() as u64);
+        for &power in powers {
+            engine.schedule(Box::new(Explosion { power }), 1);
+        }
+
+        let mut engine = engine.set_execution_order(order);
+        engine.step(&mut world);
+        world.logs
+    }
+
+    #[test]
+    fn test_execution_order_lifo_by_id() {
+        assert_eq!(
+            booms_in_order(ExecutionOrder::LifoById, &[1, 2, 3]),
+            vec!["Tick 1: BOOM 3", "Tick 1: BOOM 2", "Tick 1: BOOM 1"]
+        );
+        assert_eq!(
+            booms_in_order(ExecutionOrder::FifoById, &[1, 2, 3]),
+            vec!["Tick 1: BOOM 1", "Tick 1: BOOM 2", "Tick 1: BOOM 3"]
+        );
+    }
+
+    #[test]
+    fn test_execution_order_priority_descending() {
+        assert_eq!(
+            booms_in_order(ExecutionOrder::PriorityDescending, &[1, 5, -2, 3, 5]),
+            vec![
+                "Tick 1: BOOM 5",
+                "Tick 1: BOOM 5",
+                "Tick 1: BOOM 3",
+                "Tick 1: BOOM 1",
+                "Tick 1: BOOM -2"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_execution_order_random_is_seeded() {
+        let powers: Vec<i32> = (0..10).collect();
+        let first = booms_in_order(ExecutionOrder::Random(7), &powers);
+
+        assert_eq!(first, booms_in_order(ExecutionOrder::Random(7), &powers));
+        assert_ne!(first, booms_in_order(ExecutionOrder::FifoById, &powers));
+
+        let mut sorted = first.clone();
+        sorted.sort();
+        let mut expected = booms_in_order(ExecutionOrder::FifoById, &powers);
+        expected.sort();
+        assert_eq!(sorted, expected);
+    }
+
+    #[test]
+    fn test_execution_order_applies_to_later_schedules() {
+        let mut world = TestWorld { gold: 0, logs: vec![] };
+        let mut engine = Engine::build().set_execution_order(ExecutionOrder::LifoById);
+        engine.schedule(Box::new(Explosion { power: 1 }), 1);
+        engine.schedule(Box::new(Explosion { power: 2 }), 1);
+
+        engine.step(&mut world);
+
+        assert_eq!(world.logs, vec!["Tick 1: BOOM 2", "Tick 1: BOOM 1"]);
+    }
+
+    #[test]
+    fn test_total_events_deferred_counts_future_pops() {
+        let mut world = TestWorld { gold: 0, logs: vec![] };
+        let mut engine = Engine::build();
+        engine.schedule(Box::new(Explosion { power: 1 }), 3);
+
+        engine.step(&mut world);
+        engine.step(&mut world);
+        assert_eq!(engine.total_events_deferred(), 2);
+
+        engine.step(&mut world);
+        engine.step(&mut world);
+        assert_eq!(engine.total_events_deferred(), 2);
+        assert_eq!(world.logs, vec!["Tick 3: BOOM 1"]);
+    }
+
+    #[test]
+    fn test_inspector_plugins_see_the_lifecycle() {
+        let mut world = TestWorld { gold: 0, logs: vec![] };
+        let mut engine = Engine::build()
+            .with_inspector_plugin(TimelinePlugin::new())
+            .with_inspector_plugin(MetricsPlugin::new());
+
+        let kept = engine.schedule(Box::new(Explosion { power: 1 }), 1).id();
+        let dropped = engine.schedule(Box::new(Explosion { power: 2 }), 2).id();
+        engine.cancel_all_except(&HashSet::from([kept]));
+        engine.step(&mut world);
+
+        let timeline = engine.inspector_plugin::<TimelinePlugin>().unwrap();
+        assert_eq!(
+            timeline.entries(),
+            &[
+                TimelineEntry::Scheduled { id: kept, tick: 1 },
+                TimelineEntry::Scheduled { id: dropped, tick: 2 },
+                TimelineEntry::Cancelled { id: dropped },
+                TimelineEntry::Executed { id: kept, tick: 1 },
+                TimelineEntry::TickEnd { tick: 1, queue_size: 0 },
+            ]
+        );
+
+        let metrics = engine.inspector_plugin::<MetricsPlugin>().unwrap();
+        assert_eq!((metrics.scheduled, metrics.executed, metrics.cancelled), (2, 1, 1));
+        assert!(engine.inspector_plugin::<LoggingPlugin>().is_none());
+    }
+
+    // spawns `remaining` more copies of itself at the same tick
+    struct Echo {
+        remaining: u32,
+    }
+
+    impl Event<TestWorld> for Echo {
+        fn execute(&mut self, world: &mut TestWorld, ctx: &mut EventCtx<TestWorld>) -> EventResult {
+            world.logs.push(format!("Tick {}: echo {}", ctx.tick(), self.remaining));
+            if self.remaining > 0 {
+                ctx.scheduler().schedule(Box::new(Echo { remaining: self.remaining - 1 }), 0);
+            }
+
+            EventResult::Continue
+        }
+    }
+
+    #[test]
+    fn test_flush_at_tick_includes_same_tick_spawns() {
+        let mut world = TestWorld { gold: 0, logs: vec![] };
+        let mut engine = Engine::build().max_executions_per_tick(1);
+        engine.schedule(Box::new(Echo { remaining: 2 }), 100);
+        engine.schedule(Box::new(Explosion { power: 1 }), 100);
+        engine.schedule(Box::new(Explosion { power: 2 }), 101);
+
+        assert_eq!(engine.flush_at_tick(100, &mut world), 4);
+        assert_eq!(engine.get_current_tick(), 0);
+        assert_eq!(engine.get_queue_size(), 1);
+        assert_eq!(
+            world.logs,
+            vec![
+                "Tick 100: echo 2",
+                "Tick 100: BOOM 1",
+                "Tick 100: echo 1",
+                "Tick 100: echo 0"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_events_scheduled_by_event_reports_ticks() {
+        let mut world = TestWorld { gold: 0, logs: vec![] };
+        let mut engine = Engine::build();
+        let parent = engine.schedule(Box::new(Lineage { generations: 1 }), 2).id();
+        engine.schedule(Box::new(Explosion { power: 1 }), 9);
+
+        engine.step_until(2, &mut world);
+
+        let mut children = engine.events_scheduled_by_event(parent);
+        children.sort();
+        assert_eq!(children.iter().map(|&(tick, _)| tick).collect::<Vec<_>>(), vec![3, 12]);
+        for (tick, id) in children {
+            assert_eq!(engine.get_scheduled_tick_for(id), Some(tick));
+            assert!(engine.events_scheduled_by_event(id).is_empty());
+        }
+    }
+
+    #[test]
+    fn test_apply_event_transform_replaces_events_and_ticks() {
+        let mut world = TestWorld { gold: 0, logs: vec![] };
+        let mut engine = Engine::build();
+        let id = engine.schedule(Box::new(Explosion { power: 1 }), 2).id();
+        engine.schedule(Box::new(Explosion { power: 2 }), 4);
+
+        let transformed = engine.apply_event_transform(|event, tick| {
+            let power = if event.type_name().ends_with("Explosion") { tick as i32 * 10 } else { 0 };
+            (Box::new(Explosion { power }), tick + 1)
+        });
+
+        assert_eq!(transformed, 2);
+        assert_eq!(engine.get_scheduled_tick_for(id), Some(3));
+
+        engine.step_until(5, &mut world);
+        assert_eq!(world.logs, vec!["Tick 3: BOOM 20", "Tick 5: BOOM 40"]);
+    }
+
+    #[test]
+    fn test_schedule_one_of_co_schedules_in_order() {
+        let mut world = TestWorld { gold: 0, logs: vec![] };
+        let mut engine = Engine::build();
+        let ids = engine
+            .schedule_one_of(
+                vec![Box::new(Explosion { power: 1 }), Box::new(Explosion { power: 2 })],
+                3,
+            )
+            .unwrap();
+
+        assert_eq!(ids.len(), 2);
+        assert!(ids[0] < ids[1]);
+        assert!(ids.iter().all(|&id| engine.get_scheduled_tick_for(id) == Some(3)));
+
+        engine.cancel_all_except(&HashSet::from([ids[1]]));
+        engine.step_until(3, &mut world);
+        assert_eq!(world.logs, vec!["Tick 3: BOOM 2"]);
+    }
+
+    #[test]
+    fn test_schedule_one_of_is_all_or_nothing() {
+        let mut engine = Engine::<TestWorld>::build().with_max_events_per_type::<Explosion>(2);
+        engine.schedule(Box::new(Explosion { power: 0 }), 1);
+
+        let group: Vec<Box<dyn Event<TestWorld>>> =
+            (1..=3).map(|power| Box::new(Explosion { power }) as _).collect();
+        assert_eq!(engine.schedule_one_of(group, 3), Err(ScheduleError::TypeLimitReached));
+        assert_eq!(engine.get_queue_size(), 1);
+        assert_eq!(engine.peek_next_tick(), Some(1));
+    }
+
+    #[test]
+    fn test_event_quota_defers_excess_tagged_events() {
+        let mut world = TestWorld { gold: 0, logs: vec![] };
+        let mut engine = Engine::build()
+            .max_executions_per_tick(10)
+            .with_event_quota(EventQuota::new().with_limit("ai", 1));
+        engine.schedule_tagged(Box::new(Explosion { power: 1 }), 1, "ai");
+        engine.schedule_tagged(Box::new(Explosion { power: 2 }), 1, "ai");
+        engine.schedule_tagged(Box::new(Explosion { power: 3 }), 1, "combat");
+        engine.schedule(Box::new(Explosion { power: 4 }), 1);
+
+        engine.step(&mut world);
+        assert_eq!(world.logs, vec!["Tick 1: BOOM 1", "Tick 1: BOOM 3", "Tick 1: BOOM 4"]);
+        assert_eq!(engine.get_queue_size(), 1);
+
+        engine.step(&mut world);
+        assert_eq!(world.logs.last().unwrap(), "Tick 2: BOOM 2");
+    }
+
+    #[test]
+    fn test_recent_throughput_uses_sliding_window() {
+        let mut world = TestWorld { gold: 0, logs: vec![] };
+        let mut engine = Engine::build().with_throughput_window(3);
+        assert_eq!(engine.recent_throughput(5), 0.0);
+
+        for power in 0..4 {
+            engine.schedule(Box::new(Explosion { power }), 1);
+        }
+        engine.step(&mut world);
+        engine.step(&mut world);
+        engine.schedule(Box::new(Explosion { power: 9 }), 1);
+        engine.step(&mut world);
+        engine.step(&mut world);
+
+        // per-tick counts were 4, 0, 1, 0 and only the last three are kept
+        assert_eq!(engine.recent_throughput(1), 0.0);
+        assert_eq!(engine.recent_throughput(2), 0.5);
+        assert_eq!(engine.recent_throughput(10), 1.0 / 3.0);
+    }
+
+    #[test]
+    fn test_event_backoff_delays_when_queue_is_deep() {
+        let mut engine: Engine<TestWorld> =
+            Engine::build().with_event_backoff(|depth| if depth >= 2 { 5 } else { 0 });
+
+        let first = engine.schedule(Box::new(Explosion { power: 1 }), 1).id();
+        let second = engine.schedule(Box::new(Explosion { power: 2 }), 1).id();
+        let third = engine.schedule(Box::new(Explosion { power: 3 }), 1).id();
+
+        assert_eq!(engine.get_scheduled_tick_for(first), Some(1));
+        assert_eq!(engine.get_scheduled_tick_for(second), Some(1));
+        assert_eq!(engine.get_scheduled_tick_for(third), Some(6));
+    }
+
+    #[test]
+    fn test_total_ticks_with_and_without_events() {
+        let mut world = TestWorld { gold: 0, logs: vec![] };
+        let mut engine = Engine::build();
+        engine.schedule(Box::new(Explosion { power: 1 }), 2);
+        engine.schedule(Box::new(Explosion { power: 2 }), 2);
+        engine.schedule(Box::new(Explosion { power: 3 }), 5);
+
+        engine.step_until(6, &mut world);
+
+        assert_eq!(engine.total_ticks_with_events(), 2);
+        assert_eq!(engine.total_ticks_without_events(), 4);
+    }
+
+    #[test]
+    fn test_cancel_pending_fired_and_unknown_ids() {
+        let mut world = TestWorld { gold: 0, logs: vec![] };
+        let mut engine = Engine::build();
+        let fired = engine.schedule(Box::new(Explosion { power: 1 }), 1).id();
+        let pending = engine.schedule(Box::new(Explosion { power: 2 }), 3).id();
+        engine.step(&mut world);
+
+        assert!(!engine.cancel(fired));
+        assert!(!engine.cancel(EventId(999)));
+        assert!(engine.cancel(pending));
+        assert_eq!(engine.get_queue_size(), 0);
+    }
+
+    #[test]
+    fn test_cancelled_bomb_never_fires() {
+        let mut world = TestWorld { gold: 0, logs: vec![] };
+        let mut engine = Engine::build();
+        let bomb = engine.schedule(Box::new(Explosion { power: 100 }), 10);
+
+        engine.step_until(5, &mut world);
+        assert!(bomb.cancel(&mut engine));
+        assert_eq!(engine.get_queue_size(), 0);
+        assert!(!bomb.is_pending(&engine));
+
+        engine.step_until(20, &mut world);
+        assert!(world.logs.is_empty());
+        assert!(!bomb.cancel(&mut engine));
+    }
+
+    #[test]
+    fn test_reschedule_moves_pending_event() {
+        let mut world = TestWorld { gold: 0, logs: vec![] };
+        let mut engine = Engine::build();
+        let timeout = engine.schedule(Box::new(Explosion { power: 1 }), 3).id();
+        let other = engine.schedule(Box::new(Explosion { power: 2 }), 6).id();
+
+        engine.step_until(2, &mut world);
+        assert_eq!(engine.reschedule(timeout, 4), Ok(()));
+        assert_eq!(engine.get_scheduled_tick_for(timeout), Some(6));
+
+        engine.step_until(6, &mut world);
+        assert_eq!(world.logs, vec!["Tick 6: BOOM 1", "Tick 6: BOOM 2"]);
+        assert_eq!(engine.reschedule(timeout, 1), Err(RescheduleError::NotFound));
+        assert_eq!(engine.reschedule(other, 1), Err(RescheduleError::NotFound));
+    }
+
+    #[test]
+    fn test_peek_next_tick_does_not_advance() {
+        let mut world = TestWorld { gold: 0, logs: vec![] };
+        let mut engine = Engine::build();
+        assert_eq!(engine.peek_next_tick(), None);
+
+        engine.schedule(Box::new(Explosion { power: 1 }), 500);
+        engine.schedule(Box::new(Explosion { power: 2 }), 20);
+
+        assert_eq!(engine.peek_next_tick(), Some(20));
+        assert_eq!(engine.get_current_tick(), 0);
+
+        engine.step_until(20, &mut world);
+        assert_eq!(engine.peek_next_tick(), Some(500));
+    }
+
+    // holds a reservation on some gold until it fires or is cancelled
+    struct Reservation {
+        amount: i32,
+    }
+
+    impl Event<TestWorld> for Reservation {
+        fn execute(&mut self, world: &mut TestWorld, ctx: &mut EventCtx<TestWorld>) -> EventResult {
+            world.logs.push(format!("Tick {}: spent {}", ctx.tick(), self.amount));
+            if self.amount > 1 {
+                ctx.scheduler().schedule(Box::new(Reservation { amount: self.amount / 2 }), 2);
+                ctx.scheduler().schedule(Box::new(Reservation { amount: self.amount / 2 }), 3);
+            }
+
+            EventResult::Continue
+        }
+
+        fn on_cancel(&self, world: &mut TestWorld, current_tick: u64) {
+            world.gold += self.amount;
+            world.logs.push(format!("Tick {}: released {}", current_tick, self.amount));
+        }
+    }
+
+    #[test]
+    fn test_cascade_cancel_runs_on_cancel_hooks() {
+        let mut world = TestWorld { gold: 0, logs: vec![] };
+        let mut engine = Engine::build();
+        let root = engine.schedule(Box::new(Reservation { amount: 8 }), 1).id();
+        engine.schedule(Box::new(Explosion { power: 1 }), 10);
+
+        engine.step(&mut world);
+        assert_eq!(engine.cascade_cancel(root, &mut world), 2);
+
+        assert_eq!(world.gold, 8);
+        assert_eq!(
+            world.logs,
+            vec!["Tick 1: spent 8", "Tick 1: released 4", "Tick 1: released 4"]
+        );
+        assert_eq!(engine.get_queue_size(), 1);
+        assert_eq!(engine.cascade_cancel(root, &mut world), 0);
+    }
+
+    #[test]
+    fn test_scheduling_efficiency_counts_cancellations() {
+        let mut world = TestWorld { gold: 0, logs: vec![] };
+        let mut engine = Engine::build().with_max_events_per_type::<Explosion>(3);
+        assert_eq!(engine.get_scheduling_efficiency(), 0.0);
+
+        let ids: Vec<EventId> = (0..4)
+            .map(|power| engine.schedule(Box::new(Explosion { power }), 1).id())
+            .collect();
+        engine.cancel(ids[0]);
+        engine.step(&mut world);
+
+        // the fourth was rejected by the type limit, so only three count as scheduled
+        assert_eq!(engine.get_total_events_scheduled(), 3);
+        assert_eq!(engine.get_scheduling_efficiency(), 2.0 / 3.0);
+    }
+
+    #[test]
+    fn test_schedule_handles_are_copy_and_hashable() {
+        let mut engine: Engine<TestWorld> = Engine::build();
+        let bomb = engine.schedule(Box::new(Explosion { power: 1 }), 7);
+        let copy = bomb;
+
+        assert_eq!(bomb, copy);
+        assert_eq!(bomb.due_tick(), 7);
+        assert_eq!(bomb.get_tick(&engine), Some(7));
+
+        let handles = HashSet::from([bomb, copy, engine.schedule(Box::new(Explosion { power: 2 }), 7)]);
+        assert_eq!(handles.len(), 2);
+        assert_eq!(engine.reschedule(bomb.id(), 1), Ok(()));
+        assert_eq!(bomb.due_tick(), 7);
+        assert_eq!(bomb.get_tick(&engine), Some(1));
+    }
+
+    #[test]
+    fn test_step_until_empty_returns_last_fired_tick() {
+        let mut world = TestWorld { gold: 0, logs: vec![] };
+        let mut engine = Engine::build().max_executions_per_tick(1);
+        engine.schedule(Box::new(Lineage { generations: 2 }), 1);
+
+        // the last explosion is spawned at tick 2 and lands at tick 12
+        assert_eq!(engine.step_until_empty(&mut world), 12);
+        assert_eq!(engine.get_queue_size(), 0);
+        assert_eq!(engine.step_until_empty(&mut world), 12);
+    }
+
+    #[test]
+    fn test_step_until_empty_bounded_stops_recurring_events() {
+        let mut world = TestWorld { gold: 0, logs: vec![] };
+        let mut engine = Engine::build();
+        engine.schedule(Box::new(Miner { amount: 1 }), 1);
+
+        assert_eq!(engine.step_until_empty_bounded(&mut world, 50), None);
+        assert_eq!(engine.get_current_tick(), 50);
+
+        let mut engine = Engine::build();
+        engine.schedule(Box::new(Explosion { power: 1 }), 3);
+        assert_eq!(engine.step_until_empty_bounded(&mut world, 50), Some(3));
+    }
+
+    #[test]
+    fn test_step_until_empty_bounded_stops_when_halted() {
+        let mut world = TestWorld { gold: 0, logs: vec![] };
+        let mut engine = Engine::build();
+        engine.schedule(Box::new(GameOver), 1);
+        engine.schedule(Box::new(Miner { amount: 1 }), 2);
+        engine.step(&mut world);
+        assert!(engine.is_halted());
+
+        assert_eq!(engine.step_until_empty_bounded(&mut world, 50), None);
+        assert_eq!(engine.get_current_tick(), 1);
+        assert_eq!(world.gold, 0);
+    }
+
+    #[test]
+    fn test_schedule_with_priority_orders_within_a_tick() {
+        let mut world = TestWorld { gold: 0, logs: vec![] };
+        let mut engine = Engine::build();
+        engine.schedule(Box::new(Explosion { power: 0 }), 1);
+        engine.schedule_with_priority(Box::new(Explosion { power: -1 }), 1, -1);
+        engine.schedule_with_priority(Box::new(Explosion { power: 10 }), 1, 10);
+        engine.schedule_with_priority(Box::new(Explosion { power: 100 }), 2, 100);
+        engine.schedule_with_priority(Box::new(Explosion { power: 5 }), 1, 10);
+
+        engine.step_until(2, &mut world);
+
+        assert_eq!(
+            world.logs,
+            vec![
+                "Tick 1: BOOM -1",
+                "Tick 1: BOOM 0",
+                "Tick 1: BOOM 10",
+                "Tick 1: BOOM 5",
+                "Tick 2: BOOM 100"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_step_until_all_types_executed() {
+        let mut world = TestWorld { gold: 0, logs: vec![] };
+        let mut engine = Engine::build();
+        engine.schedule(Box::new(Explosion { power: 1 }), 2);
+        engine.schedule(Box::new(Miner { amount: 1 }), 4);
+        engine.schedule(Box::new(Explosion { power: 2 }), 6);
+
+        let types = [TypeId::of::<Explosion>(), TypeId::of::<Miner>()];
+        assert_eq!(engine.step_until_all_types_executed(&mut world, types), Some(4));
+
+        let mut engine = Engine::build();
+        engine.schedule(Box::new(Explosion { power: 1 }), 2);
+        assert_eq!(engine.step_until_all_types_executed(&mut world, types), None);
+        assert_eq!(engine.get_current_tick(), 2);
+    }
+
+    #[test]
+    fn test_step_reports_executed_and_deferred() {
+        let mut world = TestWorld { gold: 0, logs: vec![] };
+        let mut engine = Engine::build().max_executions_per_tick(2);
+        for power in 0..3 {
+            engine.schedule(Box::new(Explosion { power }), 1);
+        }
+        engine.schedule(Box::new(Explosion { power: 9 }), 5);
+
+        assert_eq!(
+            engine.step(&mut world),
+            StepReport { tick: 1, executed: 2, deferred: 1, queue_len_after: 2, halted: false }
+        );
+        assert_eq!(
+            engine.step(&mut world),
+            StepReport { tick: 2, executed: 1, deferred: 0, queue_len_after: 1, halted: false }
+        );
+        assert_eq!(engine.try_step(&mut world).unwrap().executed, 0);
+    }
+
+    #[test]
+    fn test_spawn_depth_histogram() {
+        let mut world = TestWorld { gold: 0, logs: vec![] };
+        let mut engine = Engine::build();
+        engine.schedule(Box::new(Lineage { generations: 2 }), 1);
+        engine.schedule(Box::new(Explosion { power: 1 }), 50);
+
+        engine.step_until(2, &mut world);
+
+        // depth 0: the top-level explosion; depth 1: the first child explosion;
+        // depth 2: the grandchild lineage and its sibling explosion
+        assert_eq!(engine.spawn_depth_histogram(), HashMap::from([(0, 1), (1, 1), (2, 2)]));
+    }
+
+    #[test]
+    fn test_advance_to_next_event_skips_idle_ticks() {
+        let mut world = TestWorld { gold: 0, logs: vec![] };
+        let mut engine = Engine::build().max_executions_per_tick(1);
+        engine.schedule(Box::new(Explosion { power: 1 }), 400_000);
+        engine.schedule(Box::new(Explosion { power: 2 }), 400_000);
+        engine.schedule(Box::new(Lineage { generations: 1 }), 1_000_000);
+
+        assert_eq!(engine.advance_to_next_event(&mut world), Some(400_000));
+        assert_eq!(engine.clock().current_time(), 400_000);
+        // the second explosion is over the per-tick cap and runs on the following tick
+        assert_eq!(engine.advance_to_next_event(&mut world), Some(400_001));
+
+        // lineage spawns work at 1_000_001 and 1_000_010 that must not be skipped
+        assert_eq!(engine.run_until_idle(&mut world), Ok(1_000_010));
+        assert_eq!(
+            world.logs,
+            vec![
+                "Tick 400000: BOOM 1",
+                "Tick 400001: BOOM 2",
+                "Tick 1000000: generation 1",
+                "Tick 1000001: generation 0",
+                "Tick 1000010: BOOM 0"
+            ]
+        );
+        assert_eq!(engine.advance_to_next_event(&mut world), None);
+    }
+
+    #[test]
+    fn test_run_until_idle_stops_at_the_wall_clock_limit() {
+        let mut world = TestWorld { gold: 0, logs: vec![] };
+        let mut engine = Engine::build().with_wall_clock_limit(0.0);
+        engine.schedule(Box::new(Explosion { power: 1 }), 1_000);
+
+        assert_eq!(engine.run_until_idle(&mut world), Err(SimulationError::WallClockLimitExceeded));
+        assert_eq!(
+            engine.try_advance_to_next_event(&mut world),
+            Err(SimulationError::WallClockLimitExceeded)
+        );
+        assert!(world.logs.is_empty());
+        assert_eq!(engine.get_current_tick(), 0);
+        assert_eq!(engine.get_queue_size(), 1);
+    }
+
+    #[test]
+    fn test_peek_next_id_follows_recurring_event() {
+        let mut world = TestWorld { gold: 0, logs: vec![] };
+        let mut engine = Engine::build();
+        assert_eq!(engine.peek_next_id(), None);
+
+        let first = engine.schedule(Box::new(Miner { amount: 1 }), 2).id();
+        assert_eq!(engine.peek_next_id(), Some(first));
+
+        engine.step_until(2, &mut world);
+        let requeued = engine.peek_next_id().unwrap();
+        assert_ne!(requeued, first);
+        assert_eq!(engine.peek_next_tick(), Some(7));
+        assert_eq!(engine.get_scheduled_tick_for(requeued), Some(7));
+    }
+
+    #[test]
+    fn test_coalesce_duplicate_ticks_batches_same_tick_events() {
+        let mut world = TestWorld { gold: 0, logs: vec![] };
+        let mut engine = Engine::build().max_executions_per_tick(1);
+        for power in 0..4 {
+            engine.schedule(Box::new(Explosion { power }), 2);
+        }
+        engine.schedule_with_priority(Box::new(Explosion { power: 9 }), 2, -1);
+        engine.schedule(Box::new(Explosion { power: 5 }), 3);
+
+        assert_eq!(engine.coalesce_duplicate_ticks(), 1);
+        assert_eq!(engine.get_queue_size(), 2);
+
+        engine.step_until(3, &mut world);
+        assert_eq!(
+            world.logs,
+            vec![
+                "Tick 2: BOOM 9",
+                "Tick 2: BOOM 0",
+                "Tick 2: BOOM 1",
+                "Tick 2: BOOM 2",
+                "Tick 2: BOOM 3",
+                "Tick 3: BOOM 5"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_step_n_advances_exactly_n_ticks() {
+        let mut world = TestWorld { gold: 0, logs: vec![] };
+        let mut engine = Engine::build();
+        engine.schedule(Box::new(Miner { amount: 1 }), 1);
+
+        assert_eq!(engine.step_n(11, &mut world), 3);
+        assert_eq!(engine.get_current_tick(), 11);
+        assert_eq!(engine.step_n(0, &mut world), 0);
+        assert_eq!(engine.get_current_tick(), 11);
+    }
+
+    #[test]
+    fn test_wall_clock_limit_stops_stepping() {
+        let mut world = TestWorld { gold: 0, logs: vec![] };
+        let mut engine = Engine::build().with_wall_clock_limit(3600.0);
+        assert!(engine.try_step(&mut world).is_ok());
+
+        let mut engine = Engine::build().with_wall_clock_limit(0.0);
+        engine.schedule(Box::new(Explosion { power: 1 }), 1);
+        assert_eq!(engine.try_step(&mut world), Err(SimulationError::WallClockLimitExceeded));
+        assert_eq!(engine.get_current_tick(), 0);
+        assert_eq!(engine.get_queue_size(), 1);
+    }
+
+    #[test]
+    fn test_schedule_at_absolute_ticks() {
+        let mut world = TestWorld { gold: 0, logs: vec![] };
+        let mut engine = Engine::build();
+        assert_eq!(
+            engine.schedule_at(Box::new(Explosion { power: 0 }), 0).unwrap_err(),
+            ScheduleError::TickInPast
+        );
+
+        let handle = engine.schedule_at(Box::new(Explosion { power: 3 }), 3).unwrap();
+        assert_eq!(handle.due_tick(), 3);
+        let last = engine.schedule_at(Box::new(Explosion { power: 9 }), u64::MAX).unwrap();
+        assert_eq!(engine.get_scheduled_tick_for(last.id()), Some(u64::MAX));
+
+        engine.step_until(4, &mut world);
+        assert_eq!(world.logs, vec!["Tick 3: BOOM 3"]);
+        for tick in [3, 4] {
+            assert_eq!(
+                engine.schedule_at(Box::new(Explosion { power: 0 }), tick).unwrap_err(),
+                ScheduleError::TickInPast
+            );
+        }
+        assert_eq!(engine.get_queue_size(), 1);
+    }
+
+    #[test]
+    fn test_starting_tick_offsets_the_initial_pool() {
+        let mut world = TestWorld { gold: 0, logs: vec![] };
+        let mut engine = Engine::build()
+            .initial_event_pool(vec![(Box::new(Explosion { power: 1 }), 2)])
+            .starting_tick(1000);
+        engine.schedule(Box::new(Explosion { power: 2 }), 3);
+        assert_eq!(engine.get_current_tick(), 1000);
+
+        engine.step_until(1003, &mut world);
+
+        assert_eq!(world.logs, vec!["Tick 1002: BOOM 1", "Tick 1003: BOOM 2"]);
+        assert_eq!(engine.get_total_events_executed(), 2);
+    }
+
+    #[test]
+    fn test_snapshot_queue_compares_engines() {
+        let build = || {
+            let mut engine = Engine::<TestWorld>::build();
+            engine.schedule(Box::new(Explosion { power: 1 }), 4);
+            engine.schedule(Box::new(Explosion { power: 2 }), 2);
+            engine
+        };
+        let mut a = build();
+        let b = build();
+        assert_eq!(a.snapshot_queue(), b.snapshot_queue());
+        assert_eq!(
+            a.snapshot_queue().collect::<Vec<_>>(),
+            vec![(2, EventId(2)), (4, EventId(1))]
+        );
+
+        a.schedule(Box::new(Explosion { power: 3 }), 1);
+        let snapshot = a.snapshot_queue();
+        assert_ne!(snapshot, b.snapshot_queue());
+        a.step(&mut TestWorld { gold: 0, logs: vec![] });
+        assert_eq!(snapshot.len(), 3);
+        assert_eq!(a.snapshot_queue(), b.snapshot_queue());
+    }
+
+    #[test]
+    fn test_schedule_repeating_forever() {
+        let mut world = TestWorld { gold: 0, logs: vec![] };
+        let mut engine = Engine::build();
+        let handle = engine.schedule_repeating(Box::new(Explosion { power: 1 }), 5, 5, None);
+
+        engine.step_until(20, &mut world);
+        assert_eq!(
+            world.logs,
+            vec!["Tick 5: BOOM 1", "Tick 10: BOOM 1", "Tick 15: BOOM 1", "Tick 20: BOOM 1"]
+        );
+        assert_eq!(handle.get_tick(&engine), Some(25));
+
+        assert!(handle.cancel(&mut engine));
+        engine.step_until(40, &mut world);
+        assert_eq!(world.logs.len(), 4);
+    }
+
+    #[test]
+    fn test_schedule_repeating_a_fixed_number_of_times() {
+        let mut world = TestWorld { gold: 0, logs: vec![] };
+        let mut engine = Engine::build();
+        let handle = engine.schedule_repeating(Box::new(Payday), 1, 4, Some(2));
+        engine.schedule_repeating(Box::new(Explosion { power: 0 }), 1, 1, Some(0));
+
+        engine.step_until(20, &mut world);
+
+        assert_eq!(world.gold, 6);
+        assert_eq!(world.logs, vec!["Tick 2: BOOM 1", "Tick 6: BOOM 1"]);
+        assert!(!handle.is_pending(&engine));
+        assert_eq!(engine.get_total_events_executed(), 4);
+    }
+
+    #[test]
+    fn test_schedule_repeating_zero_times_gets_its_own_id() {
+        let mut world = TestWorld { gold: 0, logs: vec![] };
+        let mut engine = Engine::build();
+        let earlier = engine.schedule(Box::new(Explosion { power: 1 }), 2);
+        let nothing = engine.schedule_repeating(Box::new(Explosion { power: 2 }), 1, 1, Some(0));
+
+        assert_ne!(nothing.id(), earlier.id());
+        assert!(!nothing.cancel(&mut engine));
+        assert!(earlier.is_pending(&engine));
+
+        engine.step_until(2, &mut world);
+        assert_eq!(world.logs, vec!["Tick 2: BOOM 1"]);
+    }
+
+    #[test]
+    fn test_snapshot_and_restore_replays_the_same_future() {
+        let mut engine = Engine::build();
+        engine.schedule(Box::new(Explosion { power: 1 }), 2);
+        engine.schedule(Box::new(Explosion { power: 2 }), 4);
+        engine.step(&mut TestWorld { gold: 0, logs: vec![] });
+
+        let snapshot = engine.snapshot().unwrap();
+        assert_eq!(snapshot.current_tick(), 1);
+        assert_eq!(snapshot.queue_len(), 2);
+
+        let mut first = TestWorld { gold: 0, logs: vec![] };
+        engine.step_until(5, &mut first);
+        engine.schedule(Box::new(Explosion { power: 3 }), 1);
+
+        engine.restore_snapshot(snapshot.clone());
+        assert_eq!(engine.get_current_tick(), 1);
+        assert_eq!(engine.get_total_events_executed(), 0);
+        let mut second = TestWorld { gold: 0, logs: vec![] };
+        engine.step_until(5, &mut second);
+        assert_eq!(first.logs, second.logs);
+
+        // ids keep counting from where the snapshot left off
+        engine.restore_snapshot(snapshot);
+        assert_eq!(engine.schedule(Box::new(Explosion { power: 3 }), 1).id(), EventId(3));
+
+        engine.schedule(Box::new(Miner { amount: 4 }), 1);
+        assert!(engine.snapshot().is_none());
+    }
+
+    #[test]
+    fn test_halt_stops_mid_tick_until_resumed() {
+        let mut world = TestWorld { gold: 0, logs: vec![] };
+        let mut engine = Engine::build();
+        engine.schedule(Box::new(Explosion { power: 1 }), 3);
+        engine.schedule(Box::new(GameOver), 3);
+        engine.schedule(Box::new(Explosion { power: 2 }), 3);
+        engine.schedule(Box::new(Explosion { power: 3 }), 4);
+
+        engine.step_until(10, &mut world);
+        assert!(engine.is_halted());
+        assert_eq!(engine.get_current_tick(), 3);
+        assert_eq!(world.logs, vec!["Tick 3: BOOM 1", "Tick 3: game over"]);
+        assert_eq!(engine.get_queue_size(), 2);
+
+        assert_eq!(engine.step(&mut world).executed, 0);
+        assert_eq!(engine.get_current_tick(), 3);
+
+        engine.resume();
+        assert_eq!(engine.step_all_at_current_tick(&mut world), 1);
+        engine.step_until(10, &mut world);
+        assert_eq!(&world.logs[2..], ["Tick 3: BOOM 2", "Tick 4: BOOM 3"]);
+    }
+
+    #[test]
+    fn test_execute_hooks_wrap_every_execution() {
+        use std::cell::RefCell;
+        use std::rc::Rc;
+
+        let calls = Rc::new(RefCell::new(Vec::new()));
+        let (before, after) = (calls.clone(), calls.clone());
+        let mut world = TestWorld { gold: 0, logs: vec![] };
+        let mut engine = Engine::build()
+            .on_before_execute(move |tick, id| before.borrow_mut().push(("before", tick, id)))
+            .on_after_execute(move |tick, id| after.borrow_mut().push(("after", tick, id)));
+        engine.schedule(Box::new(Explosion { power: 1 }), 1);
+        engine.schedule(Box::new(Explosion { power: 2 }), 2);
+
+        engine.step_until(2, &mut world);
+
+        assert_eq!(
+            *calls.borrow(),
+            vec![
+                ("before", 1, EventId(1)),
+                ("after", 1, EventId(1)),
+                ("before", 2, EventId(2)),
+                ("after", 2, EventId(2))
+            ]
+        );
+    }
+
+    #[test]
+    fn test_run_until_reports_why_it_stopped() {
+        let mut world = TestWorld { gold: 0, logs: vec![] };
+        let mut engine = Engine::build();
+        engine.schedule(Box::new(Miner { amount: 10 }), 1);
+
+        let result = engine.run_until(&mut world, |world, _| world.gold >= 30, Some(100));
+        assert_eq!(result.reason, StopReason::Predicate);
+        assert_eq!((result.tick, result.ticks_run, result.events_executed), (11, 11, 3));
+
+        let result = engine.run_until(&mut world, |world, _| world.gold < 0, Some(20));
+        assert_eq!(result.reason, StopReason::TickLimit);
+        assert_eq!(result.tick, 31);
+
+        let mut engine = Engine::build();
+        engine.schedule(Box::new(Explosion { power: 1 }), 2);
+        let result = engine.run_until(&mut world, |_, _| false, None);
+        assert_eq!(result.reason, StopReason::QueueEmpty);
+        assert_eq!(result.tick, 2);
+
+        engine.schedule(Box::new(GameOver), 1);
+        let result = engine.run_until(&mut world, |_, _| false, None);
+        assert_eq!(result.reason, StopReason::Halted);
+    }
+
+    #[test]
+    fn test_drain_tick_returns_executed_ids() {
+        let mut world = TestWorld { gold: 0, logs: vec![] };
+        let mut engine = Engine::build().max_executions_per_tick(2);
+        let first = engine.schedule(Box::new(Explosion { power: 1 }), 1);
+        let second = engine.schedule_with_priority(Box::new(Explosion { power: 2 }), 1, -1);
+        let third = engine.schedule(Box::new(Explosion { power: 3 }), 1);
+
+        assert_eq!(engine.drain_tick(&mut world), vec![second.id(), first.id()]);
+        assert_eq!(engine.drain_tick(&mut world), vec![third.id()]);
+        assert!(engine.drain_tick(&mut world).is_empty());
+    }
+
+    #[test]
+    fn test_same_rng_seed_gives_identical_runs() {
+        use rand::Rng;
+
+        struct Gamble;
+
+        impl Event<TestWorld> for Gamble {
+            fn execute(&mut self, world: &mut TestWorld, ctx: &mut EventCtx<TestWorld>) -> EventResult {
+                let roll = ctx.scheduler().rng().gen_range(0..100);
+                world.gold += roll;
+                world.logs.push(format!("Tick {}: rolled {}", ctx.tick(), roll));
+                if roll < 60 {
+                    ctx.scheduler().schedule(Box::new(Gamble), 1);
+                    ctx.scheduler().schedule(Box::new(Gamble), 2);
+                }
+
+                EventResult::Continue
+            }
+        }
+
+        let run = |seed| {
+            let mut world = TestWorld { gold: 0, logs: vec![] };
+            let mut engine = Engine::build()
+                .rng_seed(seed)
+                .initial_event_pool(vec![(Box::new(Gamble), 1)]);
+            engine.step_until(30, &mut world);
+            (world.gold, world.logs)
+        };
+
+        assert_eq!(run(42), run(42));
+        assert_ne!(run(42), run(43));
+    }
+
+    #[test]
+    fn test_event_result_halt_stops_the_tick() {
+        struct Abort;
+
+        impl Event<TestWorld> for Abort {
+            fn execute(&mut self, _world: &mut TestWorld, _ctx: &mut EventCtx<TestWorld>) -> EventResult {
+                EventResult::Halt
+            }
+        }
+
+        let mut world = TestWorld { gold: 0, logs: vec![] };
+        let mut engine = Engine::build();
+        engine.schedule(Box::new(Abort), 2);
+        engine.schedule(Box::new(Explosion { power: 1 }), 2);
+
+        assert!(!engine.step(&mut world).halted);
+        let report = engine.step(&mut world);
+        assert!(report.halted);
+        assert_eq!((report.tick, report.executed, report.queue_len_after), (2, 1, 1));
+
+        engine.step_until(10, &mut world);
+        assert_eq!(engine.get_current_tick(), 2);
+        assert!(world.logs.is_empty());
+    }
+
+    #[test]
+    fn test_event_ctx_exposes_tick_and_own_id() {
+        struct WhoAmI;
+
+        impl Event<TestWorld> for WhoAmI {
+            fn execute(&mut self, world: &mut TestWorld, ctx: &mut EventCtx<TestWorld>) -> EventResult {
+                world.logs.push(format!("Tick {}: event {}", ctx.tick(), ctx.event_id()));
+                EventResult::Continue
+            }
+        }
+
+        let mut world = TestWorld { gold: 0, logs: vec![] };
+        let mut engine = Engine::build();
+        engine.schedule(Box::new(Explosion { power: 1 }), 5);
+        engine.schedule(Box::new(WhoAmI), 3);
+
+        engine.step_until(3, &mut world);
+
+        assert_eq!(world.logs, vec!["Tick 3: event 2"]);
+    }
+
+    #[test]
+    fn test_queue_iter_lists_upcoming_events_in_order() {
+        let mut engine = Engine::<TestWorld>::build();
+        let late = engine.schedule(Box::new(Explosion { power: 1 }), 9);
+        let soon = engine.schedule(Box::new(Explosion { power: 2 }), 2);
+        let sooner = engine.schedule_with_priority(Box::new(Explosion { power: 3 }), 2, -5);
+
+        let upcoming: Vec<_> = engine.queue_iter().take(2).collect();
+        assert_eq!(upcoming, vec![(2, sooner.id()), (2, soon.id())]);
+        assert!(engine.queue_iter().any(|(_, id)| id == late.id()));
+        assert_eq!(engine.get_queue_size(), 3);
+    }
+
+    #[test]
+    fn test_tick_hooks_run_around_every_tick() {
+        let mut world = TestWorld { gold: 100, logs: vec![] };
+        let mut engine = Engine::build()
+            .max_executions_per_tick(1)
+            .on_tick_start(|world: &mut TestWorld, _| world.gold -= 10)
+            .on_tick_start(|world: &mut TestWorld, tick| world.logs.push(format!("Tick {}: start", tick)))
+            .on_tick_end(|world: &mut TestWorld, tick| world.logs.push(format!("Tick {}: end", tick)));
+        engine.schedule(Box::new(Explosion { power: 1 }), 1);
+        engine.schedule(Box::new(Explosion { power: 2 }), 1);
+
+        engine.step_until(3, &mut world);
+
+        assert_eq!(world.gold, 70);
+        assert_eq!(
+            world.logs,
+            vec![
+                "Tick 1: start",
+                "Tick 1: BOOM 1",
+                "Tick 1: end",
+                "Tick 2: start",
+                "Tick 2: BOOM 2",
+                "Tick 2: end",
+                "Tick 3: start",
+                "Tick 3: end"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_clear_queue_between_steps() {
+        let mut world = TestWorld { gold: 0, logs: vec![] };
+        let mut engine = Engine::build().with_inspector_plugin(MetricsPlugin::new());
+        for delay in [2, 3, 3, 5] {
+            engine.schedule(Box::new(Explosion { power: delay as i32 }), delay);
+        }
+
+        assert_eq!(engine.clear_queue_at_tick(3), 2);
+        assert_eq!(engine.clear_queue_at_tick(4), 0);
+        engine.step_until(2, &mut world);
+
+        assert_eq!(engine.clear_queue(), 1);
+        assert_eq!(engine.get_queue_size(), 0);
+        assert_eq!(engine.inspector_plugin::<MetricsPlugin>().unwrap().cancelled, 3);
+        assert_eq!(engine.schedule(Box::new(Explosion { power: 0 }), 1).id(), EventId(5));
+
+        engine.step_until(10, &mut world);
+        assert_eq!(world.logs, vec!["Tick 2: BOOM 2", "Tick 3: BOOM 0"]);
+    }
+
+    #[test]
+    fn test_cancel_by_tag_clears_one_category() {
+        let mut world = TestWorld { gold: 0, logs: vec![] };
+        let mut engine = Engine::build().with_inspector_plugin(MetricsPlugin::new());
+        engine.schedule_tagged(Box::new(Explosion { power: 1 }), 1, "npc_ai");
+        engine.schedule_tagged(Box::new(Explosion { power: 2 }), 4, "npc_ai");
+        engine.schedule_tagged(Box::new(Explosion { power: 3 }), 2, "player_buff");
+        engine.schedule(Box::new(Explosion { power: 4 }), 3);
+
+        assert_eq!(engine.cancel_by_tag("npc_ai"), 2);
+        assert_eq!(engine.cancel_by_tag("npc_ai"), 0);
+        assert_eq!(engine.inspector_plugin::<MetricsPlugin>().unwrap().cancelled, 2);
+
+        engine.step_until(5, &mut world);
+        assert_eq!(world.logs, vec!["Tick 2: BOOM 3", "Tick 3: BOOM 4"]);
+    }
+
+    #[test]
+    fn test_schedule_fn_runs_closures_as_events() {
+        let mut world = TestWorld { gold: 0, logs: vec![] };
+        let mut engine = Engine::build();
+        let inner = |world: &mut TestWorld, tick, _: &mut Scheduler<TestWorld>| {
+            world.logs.push(format!("Tick {}: inner", tick));
+        };
+        let outer = move |world: &mut TestWorld, tick, scheduler: &mut Scheduler<TestWorld>| {
+            world.logs.push(format!("Tick {}: outer", tick));
+            scheduler.schedule_fn(inner, 2);
+        };
+        engine.schedule_fn(outer, 1);
+
+        let mut runs = 0;
+        let counter = move |world: &mut TestWorld, _, _: &mut Scheduler<TestWorld>| {
+            runs += 1;
+            world.gold += runs;
+        };
+        engine.schedule_repeating(Box::new(FnEvent::new(counter)), 1, 1, Some(3));
+
+        engine.step_until(5, &mut world);
+        assert_eq!(world.logs, vec!["Tick 1: outer", "Tick 3: inner"]);
+        assert_eq!(world.gold, 1 + 2 + 3);
+    }
+
+    #[test]
+    fn test_debug_output_summarises_the_engine() {
+        let mut world = TestWorld { gold: 0, logs: vec![] };
+        let mut engine = Engine::build().max_executions_per_tick(3);
+        engine.schedule(Box::new(Explosion { power: 1 }), 1);
+        engine.schedule(Box::new(Explosion { power: 2 }), 4);
+        engine.step(&mut world);
+
+        assert_eq!(
+            format!("{:?}", engine),
+            "Engine { current_tick: 1, max_executions_per_tick: 3, total_events_executed: 1, queue_len: 1, .. }"
+        );
+        assert!(format!("{:?}", engine.future_events_iter().next().unwrap()).contains("Explosion"));
+    }
+
+    #[test]
+    fn test_last_tick_stats_count_events_left_over_by_the_cap() {
+        let mut world = TestWorld { gold: 0, logs: vec![] };
+        let mut engine = Engine::build().max_executions_per_tick(2);
+        for power in 1..=3 {
+            engine.schedule(Box::new(Explosion { power }), 1);
+        }
+        engine.schedule(Box::new(Explosion { power: 4 }), 2);
+        assert_eq!(engine.last_tick_stats(), TickStats::default());
+
+        engine.step(&mut world);
+        assert_eq!(
+            engine.last_tick_stats(),
+            TickStats { tick: 1, events_executed: 2, events_skipped_by_limit: 1, queue_depth_after: 2 }
+        );
+
+        // the leftover keeps its place ahead of tick 2's own event
+        engine.step(&mut world);
+        assert_eq!(
+            engine.last_tick_stats(),
+            TickStats { tick: 2, events_executed: 2, events_skipped_by_limit: 0, queue_depth_after: 0 }
+        );
+        assert_eq!(world.logs[2], "Tick 2: BOOM 3");
+    }
+
+    // counts its own runs, only reporting to the world on the last one
+    struct Counter {
+        runs: i32,
+    }
+
+    impl Event<TestWorld> for Counter {
+        fn execute(&mut self, world: &mut TestWorld, ctx: &mut EventCtx<TestWorld>) -> EventResult {
+            self.runs += 1;
+            if self.runs < 3 {
+                ctx.reschedule_self(2);
+            } else {
+                world.gold = self.runs;
+            }
+
+            EventResult::Continue
+        }
+    }
+
+    #[test]
+    fn test_events_keep_their_own_state_across_reschedules() {
+        let mut world = TestWorld { gold: 0, logs: vec![] };
+        let mut engine = Engine::build();
+        let handle = engine.schedule(Box::new(Counter { runs: 0 }), 1);
+
+        engine.step_until(4, &mut world);
+        assert_eq!(world.gold, 0);
+        assert_eq!(engine.future_events_iter().next().unwrap().id, handle.id());
+
+        engine.step_until(10, &mut world);
+        assert_eq!(world.gold, 3);
+        assert_eq!(engine.get_queue_size(), 0);
+    }
+
+    #[test]
+    fn test_metrics_break_down_by_event_type() {
+        let mut world = TestWorld { gold: 0, logs: vec![] };
+        let mut engine = Engine::build().enable_metrics();
+        for amount in 1..=3 {
+            engine.schedule(Box::new(Miner { amount }), 1);
+        }
+        engine.schedule(Box::new(Explosion { power: 1 }), 4);
+
+        engine.step_until(20, &mut world);
+
+        let metrics = engine.metrics().unwrap();
+        assert_eq!(metrics.of::<Miner>(), TypeMetrics { executed: 12, pending: 3 });
+        assert_eq!(metrics.of::<Explosion>(), TypeMetrics { executed: 1, pending: 0 });
+        assert_eq!(metrics.iter().count(), 2);
+
+        engine.reset_metrics();
+        assert_eq!(engine.metrics().unwrap().of::<Miner>(), TypeMetrics { executed: 0, pending: 3 });
+        assert!(Engine::<TestWorld>::build().metrics().is_none());
+    }
+
+    #[test]
+    fn test_time_jump_skips_idle_ticks_without_stepping_them() {
+        let mut world = TestWorld { gold: 0, logs: vec![] };
+        let mut engine = Engine::build();
+        engine.step(&mut world);
+        engine.schedule(Box::new(Explosion { power: 3 }), 9_999);
+
+        assert_eq!(engine.time_jump(), 9_998);
+        assert_eq!(engine.get_current_tick(), 9_999);
+        assert_eq!(engine.time_jump(), 0);
+
+        let report = engine.step(&mut world);
+        assert_eq!((report.tick, report.executed), (10_000, 1));
+        assert_eq!(engine.total_ticks_with_events(), 1);
+        assert_eq!(engine.total_ticks_without_events(), 1);
+
+        assert_eq!(engine.time_jump(), 0);
+        assert_eq!(engine.get_current_tick(), 10_000);
+    }
+
+    #[test]
+    fn test_time_jump_stops_a_stride_short() {
+        let mut world = TestWorld { gold: 0, logs: vec![] };
+        let mut engine = Engine::build().with_tick_stride(60);
+        engine.schedule(Box::new(Explosion { power: 1 }), 600);
+
+        assert_eq!(engine.time_jump(), 540);
+        assert_eq!(engine.time_jump(), 0);
+        engine.step(&mut world);
+        assert_eq!(world.logs, vec!["Tick 600: BOOM 1"]);
+    }
+
+    #[test]
+    fn test_future_events_iter_visits_every_pending_event() {
+        let mut engine: Engine<TestWorld> = Engine::build();
+        engine.schedule(Box::new(Explosion { power: 1 }), 3);
+        engine.schedule(Box::new(Explosion { power: 2 }), 1);
+        engine.schedule(Box::new(Explosion { power: 3 }), 2);
+
+        let mut ids: Vec<u64> = engine.future_events_iter().map(|e| e.id.as_u64()).collect();
+        ids.sort();
+
+        assert_eq!(ids, vec![1, 2, 3]);
+        assert!(engine.future_events_iter().any(|e| e.id == EventId(2)));
+    }
+
+    #[test]
+    fn test_tick_sequence_hash_matches_for_identical_engines() {
+        let build = || {
+            Engine::build().initial_event_pool(vec![
+                (
+                    Box::new(Miner { amount: 1 }) as Box<dyn Event<TestWorld>>,
+                    2,
+                ),
+                (
+                    Box::new(Explosion { power: 1 }) as Box<dyn Event<TestWorld>>,
+                    4,
+                ),
+            ])
+        };
+
+        let a = build();
+        let mut b = build();
+        assert_eq!(a.tick_sequence_hash(), b.tick_sequence_hash());
+
+        b.schedule(Box::new(Explosion { power: 2 }), 4);
+        assert_ne!(a.tick_sequence_hash(), b.tick_sequence_hash());
+    }
+
+    #[test]
+    fn test_run_with_progress_reports_every_tick() {
+        struct Recorder(Vec<(u64, u64)>);
+
+        impl ProgressReporter for Recorder {
+            fn report(&mut self, current: u64, total: u64) {
+                self.0.push((current, total));
+            }
+        }
+
+        let mut world = TestWorld {
+            gold: 0,
+            logs: vec![],
+        };
+        let mut engine = Engine::build();
+        let mut recorder = Recorder(vec![]);
+
+        engine.run_with_progress(&mut world, 3, &mut recorder);
+
+        assert_eq!(recorder.0, vec![(1, 3), (2, 3), (3, 3)]);
+    }
+
+    #[test]
+    fn test_last_executed_id_tracks_latest_step() {
+        let mut world = TestWorld {
+            gold: 0,
+            logs: vec![],
+        };
+        let mut engine = Engine::build();
+        engine.schedule(Box::new(Explosion { power: 1 }), 1);
+        engine.schedule(Box::new(Explosion { power: 2 }), 1);
+
+        assert_eq!(engine.get_last_executed_id(), None);
+
+        engine.step(&mut world);
+        assert_eq!(engine.get_last_executed_id(), Some(EventId(2)));
+
+        engine.step(&mut world);
+        assert_eq!(engine.get_last_executed_id(), None);
+    }
+
+    #[test]
+    fn test_step_until_first_of_any_stops_mid_tick() {
+        let mut world = TestWorld {
+            gold: 0,
+            logs: vec![],
+        };
+        let mut engine = Engine::build().initial_event_pool(vec![
+            (
+                Box::new(Explosion { power: 1 }) as Box<dyn Event<TestWorld>>,
+                3,
+            ),
+            (
+                Box::new(Explosion { power: 2 }) as Box<dyn Event<TestWorld>>,
+                3,
+            ),
+        ]);
+
+        let stopped = engine.step_until_first_of_any(&mut world, |_, w| !w.logs.is_empty());
+
+        assert_eq!(stopped, Ok(Some(3)));
+        assert_eq!(world.logs, vec!["Tick 3: BOOM 1"]);
+        assert_eq!(engine.last_tick_stats().events_executed, 1);
+        assert_eq!(engine.get_queue_size(), 1);
+    }
+
+    #[test]
+    fn test_step_until_first_of_any_returns_none_when_drained() {
+        let mut world = TestWorld {
+            gold: 0,
+            logs: vec![],
+        };
+        let mut engine = Engine::build();
+        engine.schedule(Box::new(Explosion { power: 1 }), 2);
+
+        assert_eq!(engine.step_until_first_of_any(&mut world, |_, w| w.gold > 0), Ok(None));
+        assert_eq!(engine.get_current_tick(), 2);
+    }
+
+    #[test]
+    fn test_step_until_first_of_any_respects_the_wall_clock_limit() {
+        let mut world = TestWorld { gold: 0, logs: vec![] };
+        let mut engine = Engine::build().with_wall_clock_limit(0.0);
+        engine.schedule(Box::new(Explosion { power: 1 }), 2);
+
+        assert_eq!(
+            engine.step_until_first_of_any(&mut world, |_, w| !w.logs.is_empty()),
+            Err(SimulationError::WallClockLimitExceeded)
+        );
+        assert!(world.logs.is_empty());
+    }
+
+    #[test]
+    fn test_consistent_id_range() {
+        let mut engine: Engine<TestWorld> = Engine::build().with_consistent_id_range(1000, 10);
+
+        engine.schedule(Box::new(Explosion { power: 1 }), 1);
+        engine.schedule(Box::new(Explosion { power: 2 }), 1);
+
+        let mut ids: Vec<u64> = engine.future_events_iter().map(|e| e.id.as_u64()).collect();
+        ids.sort();
+        assert_eq!(ids, vec![1010, 1020]);
+
+        assert_eq!(engine.scheduler().schedule(Box::new(Explosion { power: 3 }), 5).id(), EventId(1030));
+    }
+
+    #[test]
+    fn test_get_scheduled_tick_for() {
+        let mut world = TestWorld {
+            gold: 0,
+            logs: vec![],
+        };
+        let mut engine = Engine::build();
+        engine.schedule(Box::new(Explosion { power: 1 }), 1);
+        engine.schedule(Box::new(Explosion { power: 2 }), 7);
+
+        assert_eq!(engine.get_scheduled_tick_for(EventId(1)), Some(1));
+        assert_eq!(engine.get_scheduled_tick_for(EventId(2)), Some(7));
+        assert_eq!(engine.get_scheduled_tick_for(EventId(3)), None);
+
+        engine.step(&mut world);
+        assert_eq!(engine.get_scheduled_tick_for(EventId(1)), None);
+    }
+
+    #[test]
+    fn test_100k_ticks_with_probabilistic_spawning() {
+        use rand::Rng;
+
+        struct CounterWorld {
+            event_count: u64,
+        }
+
+        struct SpawningEvent;
+
+        impl Event<CounterWorld> for SpawningEvent {
+            fn execute(&mut self, world: &mut CounterWorld, ctx: &mut EventCtx<CounterWorld>) -> EventResult {
+                world.event_count += 1;
+
+                // 50% chance to spawn 3 more events
+                if ctx.scheduler().rng().gen_bool(0.5) {
+                    for _ in 0..3 {
+                        ctx.scheduler().schedule(Box::new(SpawningEvent), 5);
+                    }
+                }
+
+                EventResult::Continue
+            }
         }
 
         let mut world = CounterWorld { event_count: 0 };
+        // a fixed seed keeps the run, and its length, the same every time. this one keeps the
+        // population alive instead of dying out in the first few ticks
         let mut engine = Engine::build()
+            .rng_seed(3)
             .max_executions_per_tick(1000)
             .initial_event_pool(vec![(Box::new(SpawningEvent), 1)]);
 
@@ -218,8 +4253,8 @@ mod tests {
 
         assert_eq!(engine.current_tick, 10_000);
         assert!(
-            world.event_count > 0,
-            "at least one event should have executed"
+            world.event_count > 1_000_000,
+            "the population should have kept spawning"
         );
     }
 }
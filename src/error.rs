@@ -0,0 +1,120 @@
+use crate::EventId;
+use std::error::Error;
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScheduleError {
+    /// the event's type already has as many pending events as `with_max_events_per_type` allows
+    TypeLimitReached,
+    /// the event to schedule relative to has already fired, been cancelled or never existed
+    BaseEventNotFound,
+    /// `schedule_at` was given a tick at or before `current_tick`
+    TickInPast,
+    /// the event's tick would overflow under `OverflowPolicy::Panic` or `OverflowPolicy::Error`
+    TickOverflow,
+}
+
+impl fmt::Display for ScheduleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ScheduleError::TypeLimitReached => {
+                write!(f, "pending event limit reached for this event type")
+            }
+            ScheduleError::BaseEventNotFound => write!(f, "base event is not in the queue"),
+            ScheduleError::TickInPast => write!(f, "tick is not after the current tick"),
+            ScheduleError::TickOverflow => write!(f, "scheduled tick overflowed"),
+        }
+    }
+}
+
+impl Error for ScheduleError {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RescheduleError {
+    /// the event has already fired, been cancelled or never existed
+    NotFound,
+    /// the new tick would overflow under `OverflowPolicy::Panic` or `OverflowPolicy::Error`
+    TickOverflow,
+}
+
+impl fmt::Display for RescheduleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RescheduleError::NotFound => write!(f, "event is not in the queue"),
+            RescheduleError::TickOverflow => write!(f, "rescheduled tick overflowed"),
+        }
+    }
+}
+
+impl Error for RescheduleError {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SimulationError {
+    /// advancing would overflow `current_tick` under `OverflowPolicy::Error`
+    TickOverflow,
+    /// the deadline set by `with_wall_clock_limit` has passed
+    WallClockLimitExceeded,
+}
+
+impl fmt::Display for SimulationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SimulationError::TickOverflow => write!(f, "current tick overflowed"),
+            SimulationError::WallClockLimitExceeded => write!(f, "wall clock limit exceeded"),
+        }
+    }
+}
+
+impl Error for SimulationError {}
+
+/// why `Engine::step_fallible` failed
+#[derive(Debug)]
+pub enum StepError<T = u64> {
+    /// the `Fallible` event `id` returned `error` at `tick`. events with different error types
+    /// can share an engine, so get the concrete one back with `downcast_ref`
+    Event { id: EventId, tick: T, error: Box<dyn Error> },
+    Simulation(SimulationError),
+}
+
+impl<T: fmt::Display> fmt::Display for StepError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StepError::Event { id, tick, error } => write!(f, "event {} failed at tick {}: {}", id, tick, error),
+            StepError::Simulation(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+impl<T: fmt::Debug + fmt::Display> Error for StepError<T> {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            StepError::Event { error, .. } => Some(&**error),
+            StepError::Simulation(err) => Some(err),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RegistryError {
+    /// a pending event's type was not registered with the `EventRegistry`
+    Unregistered,
+    /// saved data names a type the `EventRegistry` does not know
+    UnknownTypeName(String),
+    /// serializing or deserializing an event's data failed
+    Serde(String),
+}
+
+#[cfg(feature = "serde")]
+impl fmt::Display for RegistryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RegistryError::Unregistered => write!(f, "event type is not registered"),
+            RegistryError::UnknownTypeName(name) => write!(f, "no event type registered as {}", name),
+            RegistryError::Serde(message) => write!(f, "event data could not be converted: {}", message),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl Error for RegistryError {}
@@ -1,7 +1,53 @@
-use crate::Scheduler;
+use crate::EventCtx;
+use crate::TickTime;
+use std::any::Any;
+
+/// what the engine should do once an event's `execute` returns
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum EventResult {
+    #[default]
+    Continue,
+    /// stop like `Scheduler::halt`: the rest of the tick stays queued until `Engine::resume`
+    Halt,
+}
+
+pub trait Event<W, T: TickTime = u64>: Any {
+    /// runs the event. the engine owns it while it executes, so it can update its own fields and
+    /// carry them into its next run via `EventCtx::reschedule_self`
+    fn execute(&mut self, world: &mut W, ctx: &mut EventCtx<W, T>) -> EventResult;
+
+    fn type_name(&self) -> &'static str {
+        std::any::type_name::<Self>()
+    }
+
+    /// a readable label for logs, `Debug` output and queue introspection, e.g. the `tracing`
+    /// feature's output. defaults to `type_name`
+    fn name(&self) -> &str {
+        self.type_name()
+    }
+
+    /// called by `Engine::cascade_cancel` when this event is cancelled before it fires
+    fn on_cancel(&self, _world: &mut W, _current_tick: T) {}
+
+    /// same-tick rank under `ExecutionOrder::PriorityDescending`, higher fires first
+    fn priority(&self) -> i32 {
+        0
+    }
+
+    /// opt in to queue cloning by returning `Some(self)` from events that are `Clone`
+    fn as_clone_event(&self) -> Option<&dyn CloneEvent<W, T>> {
+        None
+    }
+}
 
-pub trait Event<W> {
-    fn execute(&self, world: &mut W, current_tick: u64, scheduler: &mut Scheduler<W>);
+pub trait CloneEvent<W, T: TickTime = u64>: Event<W, T> {
+    fn clone_event(&self) -> Box<dyn CloneEvent<W, T>>;
+}
+
+impl<W: 'static, T: TickTime, E: Event<W, T> + Clone> CloneEvent<W, T> for E {
+    fn clone_event(&self) -> Box<dyn CloneEvent<W, T>> {
+        Box::new(self.clone())
+    }
 }
 
 #[cfg(test)]
@@ -19,8 +65,9 @@ mod tests {
     }
 
     impl Event<TestWorld> for IncrementEvent {
-        fn execute(&self, world: &mut TestWorld, _current_tick: u64, _scheduler: &mut Scheduler<TestWorld>) {
+        fn execute(&mut self, world: &mut TestWorld, _ctx: &mut EventCtx<TestWorld>) -> EventResult {
             world.counter += self.amount;
+            EventResult::Continue
         }
     }
 
@@ -30,11 +77,13 @@ mod tests {
     }
 
     impl Event<TestWorld> for SchedulingEvent {
-        fn execute(&self, _world: &mut TestWorld, _current_tick: u64, scheduler: &mut Scheduler<TestWorld>) {
+        fn execute(&mut self, _world: &mut TestWorld, ctx: &mut EventCtx<TestWorld>) -> EventResult {
             for i in 0..self.schedule_count {
                 let event = Box::new(IncrementEvent { amount: 1 });
-                scheduler.schedule(event, i as u64 + 1);
+                ctx.scheduler().schedule(event, i as u64 + 1);
             }
+
+            EventResult::Continue
         }
     }
 
@@ -81,6 +130,68 @@ mod tests {
         assert_eq!(world.counter, 1);
     }
 
+    #[test]
+    fn test_type_name_resolves_through_box() {
+        let event: Box<dyn Event<TestWorld>> = Box::new(IncrementEvent { amount: 1 });
+
+        assert!(event.type_name().ends_with("IncrementEvent"));
+    }
+
+    struct Named;
+
+    impl Event<TestWorld> for Named {
+        fn execute(&mut self, _world: &mut TestWorld, _ctx: &mut EventCtx<TestWorld>) -> EventResult {
+            EventResult::Continue
+        }
+
+        fn name(&self) -> &str {
+            "named"
+        }
+    }
+
+    #[test]
+    fn test_name_defaults_to_type_name() {
+        let mut engine = Engine::<TestWorld>::build();
+        engine.schedule(Box::new(IncrementEvent { amount: 1 }), 1);
+        engine.schedule(Box::new(Named), 2);
+
+        let names: Vec<&str> = engine.future_events_iter().map(|item| item.name()).collect();
+        assert!(names.contains(&"named"));
+        assert!(names.contains(&std::any::type_name::<IncrementEvent>()));
+    }
+
+    #[derive(Clone)]
+    struct CloneableIncrement {
+        amount: u32,
+    }
+
+    impl Event<TestWorld> for CloneableIncrement {
+        fn execute(&mut self, world: &mut TestWorld, _ctx: &mut EventCtx<TestWorld>) -> EventResult {
+            world.counter += self.amount;
+            EventResult::Continue
+        }
+
+        fn as_clone_event(&self) -> Option<&dyn CloneEvent<TestWorld>> {
+            Some(self)
+        }
+    }
+
+    #[test]
+    fn test_clone_event_opt_in() {
+        let cloneable: Box<dyn Event<TestWorld>> = Box::new(CloneableIncrement { amount: 2 });
+        let plain: Box<dyn Event<TestWorld>> = Box::new(IncrementEvent { amount: 2 });
+
+        assert!(plain.as_clone_event().is_none());
+
+        let copy = cloneable.as_clone_event().unwrap().clone_event();
+        let mut world = TestWorld { counter: 0 };
+        let mut engine = Engine::build();
+        engine.schedule(copy, 1);
+        engine.step(&mut world);
+
+        assert_eq!(world.counter, 2);
+    }
+
     #[test]
     fn test_event_with_zero_schedules() {
         let mut world = TestWorld { counter: 0 };
@@ -0,0 +1,48 @@
+use crate::EventId;
+use crate::Scheduler;
+use crate::TickTime;
+use std::error::Error;
+
+/// everything an executing event gets besides the world: the tick it runs at, its own id and
+/// the scheduler for queueing or cancelling other events
+pub struct EventCtx<'a, W, T: TickTime = u64> {
+    tick: T,
+    event_id: EventId,
+    scheduler: Scheduler<'a, W, T>,
+    // the error of a failed `Fallible` event, picked up by the engine once it returns
+    pub(crate) failure: Option<Box<dyn Error>>,
+    // set by `reschedule_self`
+    pub(crate) reschedule: Option<T>,
+}
+
+impl<'a, W, T: TickTime> EventCtx<'a, W, T> {
+    pub(crate) fn new(tick: T, event_id: EventId, scheduler: Scheduler<'a, W, T>) -> Self {
+        Self {
+            tick,
+            event_id,
+            scheduler,
+            failure: None,
+            reschedule: None,
+        }
+    }
+
+    pub fn tick(&self) -> T {
+        self.tick
+    }
+
+    /// the id of the event being executed
+    pub fn event_id(&self) -> EventId {
+        self.event_id
+    }
+
+    pub fn scheduler(&mut self) -> &mut Scheduler<'a, W, T> {
+        &mut self.scheduler
+    }
+
+    /// queues this same event, with whatever state it has now, `delay` after the current tick
+    /// once it returns. it keeps its id and nothing is reallocated. for a `schedule_repeating`
+    /// entry this replaces the interval for this one run
+    pub fn reschedule_self(&mut self, delay: T) {
+        self.reschedule = Some(delay);
+    }
+}
@@ -0,0 +1,29 @@
+use std::fmt;
+
+/// identifies a scheduled event. ids are handed out by the engine and never reused
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct EventId(pub(crate) u64);
+
+impl EventId {
+    pub fn as_u64(&self) -> u64 {
+        self.0
+    }
+}
+
+impl fmt::Display for EventId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display_prints_the_raw_id() {
+        assert_eq!(EventId(42).to_string(), "42");
+        assert_eq!(EventId(42).as_u64(), 42);
+    }
+}
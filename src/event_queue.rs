@@ -0,0 +1,213 @@
+use crate::EventId;
+use crate::TickTime;
+use crate::scheduled_wrapper::ScheduledEvent;
+use priority_queue::PriorityQueue;
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, BTreeSet};
+
+// field order is the execution order: earliest tick, then lowest schedule priority,
+// then the `ExecutionOrder` key, then id
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub(crate) struct QueueKey<T = u64> {
+    pub(crate) tick: T,
+    pub(crate) priority: i32,
+    pub(crate) order: u64,
+    pub(crate) id: EventId,
+}
+
+// a queued event with the key it is ordered by
+pub(crate) type QueueEntry<W, T> = (ScheduledEvent<W, T>, Reverse<QueueKey<T>>);
+type QueueEntryRef<'a, W, T> = (&'a ScheduledEvent<W, T>, &'a Reverse<QueueKey<T>>);
+
+// the pending events, plus the ids queued at each tick kept in step with the heap so
+// questions like "how many are due" don't have to walk every entry
+pub(crate) struct EventQueue<W, T: TickTime = u64> {
+    heap: PriorityQueue<ScheduledEvent<W, T>, Reverse<QueueKey<T>>>,
+    per_tick: BTreeMap<T, BTreeSet<EventId>>,
+}
+
+impl<W, T: TickTime> EventQueue<W, T> {
+    pub(crate) fn new() -> Self {
+        Self {
+            heap: PriorityQueue::new(),
+            per_tick: BTreeMap::new(),
+        }
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.heap.len()
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.heap.is_empty()
+    }
+
+    pub(crate) fn contains(&self, id: &EventId) -> bool {
+        self.heap.contains(id)
+    }
+
+    // heap order, not execution order
+    pub(crate) fn iter(&self) -> impl Iterator<Item = (&ScheduledEvent<W, T>, &Reverse<QueueKey<T>>)> {
+        self.heap.iter()
+    }
+
+    pub(crate) fn peek(&self) -> Option<QueueEntryRef<'_, W, T>> {
+        self.heap.peek()
+    }
+
+    pub(crate) fn get(&self, id: &EventId) -> Option<QueueEntryRef<'_, W, T>> {
+        self.heap.get(id)
+    }
+
+    pub(crate) fn get_mut(&mut self, id: &EventId) -> Option<&mut ScheduledEvent<W, T>> {
+        self.heap.get_mut(id).map(|(item, _)| item)
+    }
+
+    pub(crate) fn get_priority(&self, id: &EventId) -> Option<&Reverse<QueueKey<T>>> {
+        self.heap.get_priority(id)
+    }
+
+    /// returns the previous key if an event with the same id was already queued
+    pub(crate) fn push(&mut self, item: ScheduledEvent<W, T>, key: Reverse<QueueKey<T>>) -> Option<Reverse<QueueKey<T>>> {
+        let replaced = self.heap.push(item, key);
+        if let Some(old) = replaced {
+            self.untrack(&old.0);
+        }
+        self.track(&key.0);
+
+        replaced
+    }
+
+    pub(crate) fn pop(&mut self) -> Option<QueueEntry<W, T>> {
+        let entry = self.heap.pop()?;
+        self.untrack(&entry.1.0);
+
+        Some(entry)
+    }
+
+    pub(crate) fn remove(&mut self, id: &EventId) -> Option<QueueEntry<W, T>> {
+        let entry = self.heap.remove(id)?;
+        self.untrack(&entry.1.0);
+
+        Some(entry)
+    }
+
+    pub(crate) fn change_priority(&mut self, id: &EventId, key: Reverse<QueueKey<T>>) -> Option<Reverse<QueueKey<T>>> {
+        let old = self.heap.change_priority(id, key)?;
+        self.untrack(&old.0);
+        self.track(&key.0);
+
+        Some(old)
+    }
+
+    pub(crate) fn extract_if<F>(&mut self, mut predicate: F) -> Vec<QueueEntry<W, T>>
+    where
+        F: FnMut(&ScheduledEvent<W, T>) -> bool,
+    {
+        let extracted: Vec<_> = self.heap.extract_if(|item, _| predicate(item)).collect();
+        for (_, key) in &extracted {
+            self.untrack(&key.0);
+        }
+
+        extracted
+    }
+
+    pub(crate) fn drain(&mut self) -> Vec<QueueEntry<W, T>> {
+        self.per_tick.clear();
+        self.heap.drain().collect()
+    }
+
+    pub(crate) fn into_sorted_iter(self) -> impl Iterator<Item = QueueEntry<W, T>> {
+        self.heap.into_sorted_iter()
+    }
+
+    // events queued at or before `tick`
+    pub(crate) fn due_count(&self, tick: T) -> usize {
+        self.per_tick.range(..=tick).map(|(_, ids)| ids.len()).sum()
+    }
+
+    // events queued at exactly `tick`
+    pub(crate) fn count_at(&self, tick: T) -> usize {
+        self.per_tick.get(&tick).map_or(0, BTreeSet::len)
+    }
+
+    // ids queued at exactly `tick`, lowest first
+    pub(crate) fn ids_at(&self, tick: T) -> impl Iterator<Item = EventId> + '_ {
+        self.per_tick.get(&tick).into_iter().flatten().copied()
+    }
+
+    // every tick with something queued, earliest first
+    pub(crate) fn ticks(&self) -> impl Iterator<Item = T> + '_ {
+        self.per_tick.keys().copied()
+    }
+
+    fn track(&mut self, key: &QueueKey<T>) {
+        self.per_tick.entry(key.tick).or_default().insert(key.id);
+    }
+
+    fn untrack(&mut self, key: &QueueKey<T>) {
+        if let Some(ids) = self.per_tick.get_mut(&key.tick) {
+            ids.remove(&key.id);
+            if ids.is_empty() {
+                self.per_tick.remove(&key.tick);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Event, EventCtx, EventResult};
+
+    struct Noop;
+
+    impl Event<()> for Noop {
+        fn execute(&mut self, _world: &mut (), _ctx: &mut EventCtx<()>) -> EventResult {
+            EventResult::Continue
+        }
+    }
+
+    fn key(tick: u64, id: u64) -> Reverse<QueueKey> {
+        Reverse(QueueKey { tick, priority: 0, order: 0, id: EventId(id) })
+    }
+
+    #[test]
+    fn test_due_count_follows_every_mutation() {
+        let mut queue = EventQueue::new();
+        for (id, tick) in [(1, 3), (2, 3), (3, 5), (4, 9)] {
+            queue.push(ScheduledEvent::new(EventId(id), Box::new(Noop)), key(tick, id));
+        }
+        assert_eq!(queue.due_count(4), 2);
+        assert_eq!(queue.due_count(9), 4);
+
+        queue.change_priority(&EventId(4), key(1, 4));
+        queue.remove(&EventId(1));
+        queue.pop();
+        assert_eq!(queue.due_count(4), 1);
+
+        queue.extract_if(|item| item.id == EventId(2));
+        assert_eq!(queue.due_count(u64::MAX), 1);
+
+        queue.drain();
+        assert_eq!(queue.due_count(u64::MAX), 0);
+    }
+
+    #[test]
+    fn test_count_and_ids_at_follow_every_mutation() {
+        let mut queue = EventQueue::new();
+        for (id, tick) in [(1, 3), (2, 3), (3, 5)] {
+            queue.push(ScheduledEvent::new(EventId(id), Box::new(Noop)), key(tick, id));
+        }
+        assert_eq!(queue.count_at(3), 2);
+        assert_eq!(queue.ids_at(3).collect::<Vec<_>>(), vec![EventId(1), EventId(2)]);
+
+        queue.push(ScheduledEvent::new(EventId(2), Box::new(Noop)), key(5, 2));
+        queue.change_priority(&EventId(1), key(1, 1));
+        assert_eq!(queue.count_at(3), 0);
+        assert_eq!(queue.ids_at(5).collect::<Vec<_>>(), vec![EventId(2), EventId(3)]);
+        assert_eq!(queue.ticks().collect::<Vec<_>>(), vec![1, 5]);
+        assert_eq!(queue.ids_at(7).count(), 0);
+    }
+}
@@ -0,0 +1,31 @@
+use crate::Event;
+use crate::EventId;
+use crate::TickTime;
+use rand::RngCore;
+use rand::rngs::StdRng;
+
+/// how events due at the same tick are ordered against each other
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ExecutionOrder {
+    /// lower ids fire first
+    #[default]
+    FifoById,
+    /// higher ids fire first
+    LifoById,
+    /// shuffled with an rng seeded from the given value
+    Random(u64),
+    /// higher `Event::priority` fires first, ties fall back to lower id
+    PriorityDescending,
+}
+
+impl ExecutionOrder {
+    // tie-breaker placed between the tick and the id in the queue key, smaller fires first
+    pub(crate) fn order_key<W: 'static, T: TickTime>(&self, id: EventId, event: &dyn Event<W, T>, rng: &mut StdRng) -> u64 {
+        match self {
+            ExecutionOrder::FifoById => 0,
+            ExecutionOrder::LifoById => u64::MAX - id.as_u64(),
+            ExecutionOrder::Random(_) => rng.next_u64(),
+            ExecutionOrder::PriorityDescending => (i32::MAX as i64 - event.priority() as i64) as u64,
+        }
+    }
+}
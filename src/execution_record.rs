@@ -0,0 +1,7 @@
+use crate::EventId;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExecutionRecord<T = u64> {
+    pub id: EventId,
+    pub tick: T,
+}
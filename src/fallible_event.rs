@@ -0,0 +1,149 @@
+use crate::{Event, EventCtx, EventResult, TickTime};
+use std::any::Any;
+use std::error::Error;
+
+/// an event that can fail, e.g. on world state it cannot act on. schedule it wrapped in
+/// `Fallible`; an error halts the engine like `EventResult::Halt` and `Engine::step_fallible`
+/// returns it
+pub trait FallibleEvent<W, T: TickTime = u64>: Any {
+    type Error: Error + 'static;
+
+    fn try_execute(&mut self, world: &mut W, ctx: &mut EventCtx<W, T>) -> Result<(), Self::Error>;
+}
+
+/// runs a `FallibleEvent` as an ordinary `Event`
+pub struct Fallible<F> {
+    inner: F,
+}
+
+impl<F> Fallible<F> {
+    pub fn new(inner: F) -> Self {
+        Self { inner }
+    }
+}
+
+impl<W: 'static, T: TickTime, F: FallibleEvent<W, T>> Event<W, T> for Fallible<F> {
+    fn execute(&mut self, world: &mut W, ctx: &mut EventCtx<W, T>) -> EventResult {
+        match self.inner.try_execute(world, ctx) {
+            Ok(()) => EventResult::Continue,
+            Err(error) => {
+                ctx.failure = Some(Box::new(error));
+                EventResult::Halt
+            }
+        }
+    }
+
+    fn type_name(&self) -> &'static str {
+        std::any::type_name::<F>()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Engine, EventId, StepError};
+    use std::fmt;
+
+    #[derive(Debug, PartialEq)]
+    struct OutOfStock;
+
+    impl fmt::Display for OutOfStock {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "out of stock")
+        }
+    }
+
+    impl Error for OutOfStock {}
+
+    #[derive(Debug, PartialEq)]
+    struct Closed;
+
+    impl fmt::Display for Closed {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "closed")
+        }
+    }
+
+    impl Error for Closed {}
+
+    struct Sell;
+
+    impl FallibleEvent<u32> for Sell {
+        type Error = OutOfStock;
+
+        fn try_execute(&mut self, stock: &mut u32, _ctx: &mut EventCtx<u32>) -> Result<(), OutOfStock> {
+            *stock = stock.checked_sub(1).ok_or(OutOfStock)?;
+            Ok(())
+        }
+    }
+
+    struct Close;
+
+    impl FallibleEvent<u32> for Close {
+        type Error = Closed;
+
+        fn try_execute(&mut self, _stock: &mut u32, _ctx: &mut EventCtx<u32>) -> Result<(), Closed> {
+            Err(Closed)
+        }
+    }
+
+    struct Restock;
+
+    impl Event<u32> for Restock {
+        fn execute(&mut self, stock: &mut u32, _ctx: &mut EventCtx<u32>) -> EventResult {
+            *stock += 1;
+            EventResult::Continue
+        }
+    }
+
+    // steps until an event fails, giving up after `ticks`
+    fn first_failure(engine: &mut Engine<u32>, stock: &mut u32, ticks: u64) -> Option<StepError> {
+        (0..ticks).find_map(|_| engine.step_fallible(stock).err())
+    }
+
+    #[test]
+    fn test_step_fallible_stops_at_the_failing_event() {
+        let mut stock = 1;
+        let mut engine = Engine::build();
+        for delay in [3, 7, 10] {
+            engine.schedule(Box::new(Fallible::new(Sell)), delay);
+        }
+        engine.schedule(Box::new(Restock), 7);
+        engine.schedule(Box::new(Restock), 12);
+
+        let Some(StepError::Event { id, tick, error }) = first_failure(&mut engine, &mut stock, 20) else {
+            panic!("expected an event to fail");
+        };
+        assert_eq!((id, tick), (EventId(2), 7));
+        assert_eq!(error.downcast_ref::<OutOfStock>(), Some(&OutOfStock));
+        assert!(engine.is_halted());
+        assert_eq!(engine.get_queue_size(), 3);
+
+        engine.resume();
+        engine.step_until(12, &mut stock);
+        assert_eq!(stock, 1);
+        assert_eq!(engine.get_queue_size(), 0);
+    }
+
+    #[test]
+    fn test_events_with_different_error_types_share_an_engine() {
+        let mut stock = 0;
+        let mut engine = Engine::build();
+        engine.schedule(Box::new(Fallible::new(Close)), 2);
+        engine.schedule(Box::new(Fallible::new(Sell)), 4);
+
+        let Some(StepError::Event { error, .. }) = first_failure(&mut engine, &mut stock, 10) else {
+            panic!("expected an event to fail");
+        };
+        assert_eq!(error.to_string(), "closed");
+        assert!(error.is::<Closed>());
+
+        engine.resume();
+        let Some(StepError::Event { tick, error, .. }) = first_failure(&mut engine, &mut stock, 10) else {
+            panic!("expected an event to fail");
+        };
+        assert_eq!(tick, 4);
+        assert!(error.is::<OutOfStock>());
+        assert!(first_failure(&mut engine, &mut stock, 10).is_none());
+    }
+}
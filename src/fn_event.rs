@@ -0,0 +1,32 @@
+use crate::{Event, EventCtx, EventResult, Scheduler, TickTime};
+use std::marker::PhantomData;
+
+// wraps a closure from `schedule_fn`. an `FnMut` keeps mutating its captures every time the
+// entry runs, e.g. when it is re-queued by `schedule_repeating`
+pub(crate) struct FnEvent<W, T, F> {
+    f: F,
+    _world: PhantomData<fn(&mut W, T)>,
+}
+
+impl<W, T, F> FnEvent<W, T, F> {
+    pub(crate) fn new(f: F) -> Self {
+        Self {
+            f,
+            _world: PhantomData,
+        }
+    }
+}
+
+impl<W, T, F> Event<W, T> for FnEvent<W, T, F>
+where
+    W: 'static,
+    T: TickTime,
+    F: FnMut(&mut W, T, &mut Scheduler<'_, W, T>) + 'static,
+{
+    fn execute(&mut self, world: &mut W, ctx: &mut EventCtx<W, T>) -> EventResult {
+        let tick = ctx.tick();
+        (self.f)(world, tick, ctx.scheduler());
+
+        EventResult::Continue
+    }
+}
@@ -0,0 +1,81 @@
+use crate::Engine;
+use crate::EventId;
+use crate::TickTime;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::marker::PhantomData;
+
+/// refers to a scheduled event by id. it does not borrow the engine, so it can be kept
+/// across steps (or in the world) and handed back to the engine that issued it
+pub struct EventHandle<W, T = u64> {
+    id: EventId,
+    due_tick: T,
+    _world: PhantomData<fn() -> W>,
+}
+
+impl<W: 'static, T: TickTime> EventHandle<W, T> {
+    pub(crate) fn new(id: EventId, due_tick: T) -> Self {
+        Self {
+            id,
+            due_tick,
+            _world: PhantomData,
+        }
+    }
+
+    pub fn id(&self) -> EventId {
+        self.id
+    }
+
+    /// the tick the event was scheduled for when the handle was issued, see `get_tick` for
+    /// where it is queued now
+    pub fn due_tick(&self) -> T {
+        self.due_tick
+    }
+
+    /// the tick the event is currently queued for, `None` once it has fired or been removed
+    pub fn get_tick(&self, engine: &Engine<W, T>) -> Option<T> {
+        engine.get_scheduled_tick_for(self.id)
+    }
+
+    pub fn is_pending(&self, engine: &Engine<W, T>) -> bool {
+        self.get_tick(engine).is_some()
+    }
+
+    /// removes the event from `engine`'s queue, returning false if it already fired or was removed
+    pub fn cancel(&self, engine: &mut Engine<W, T>) -> bool {
+        engine.cancel(self.id)
+    }
+}
+
+// manual impls: deriving would require `W` itself to be Copy/Eq/Hash
+impl<W, T: Copy> Clone for EventHandle<W, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<W, T: Copy> Copy for EventHandle<W, T> {}
+
+impl<W, T: PartialEq> PartialEq for EventHandle<W, T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id && self.due_tick == other.due_tick
+    }
+}
+
+impl<W, T: Eq> Eq for EventHandle<W, T> {}
+
+impl<W, T: Hash> Hash for EventHandle<W, T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.id.hash(state);
+        self.due_tick.hash(state);
+    }
+}
+
+impl<W, T: fmt::Debug> fmt::Debug for EventHandle<W, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EventHandle")
+            .field("id", &self.id)
+            .field("due_tick", &self.due_tick)
+            .finish()
+    }
+}
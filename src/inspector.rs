@@ -0,0 +1,141 @@
+use crate::EventId;
+use crate::TickTime;
+use std::any::Any;
+
+/// observes the engine's lifecycle; every hook defaults to doing nothing
+pub trait InspectorPlugin<W, T: TickTime = u64>: Any {
+    fn on_schedule(&mut self, _event_id: EventId, _tick: T) {}
+
+    fn on_execute(&mut self, _event_id: EventId, _tick: T) {}
+
+    fn on_cancel(&mut self, _event_id: EventId) {}
+
+    fn on_tick_end(&mut self, _tick: T, _queue_size: usize) {}
+}
+
+/// prints every lifecycle event to stderr
+#[derive(Debug, Default)]
+pub struct LoggingPlugin;
+
+impl LoggingPlugin {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl<W, T: TickTime> InspectorPlugin<W, T> for LoggingPlugin {
+    fn on_schedule(&mut self, event_id: EventId, tick: T) {
+        eprintln!("scheduled event {} for tick {}", event_id, tick);
+    }
+
+    fn on_execute(&mut self, event_id: EventId, tick: T) {
+        eprintln!("executed event {} at tick {}", event_id, tick);
+    }
+
+    fn on_cancel(&mut self, event_id: EventId) {
+        eprintln!("cancelled event {}", event_id);
+    }
+
+    fn on_tick_end(&mut self, tick: T, queue_size: usize) {
+        eprintln!("tick {} ended, {} queued", tick, queue_size);
+    }
+}
+
+/// running totals over every hook
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct MetricsPlugin {
+    pub scheduled: u64,
+    pub executed: u64,
+    pub cancelled: u64,
+    pub ticks: u64,
+    pub peak_queue_size: usize,
+}
+
+impl MetricsPlugin {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl<W, T: TickTime> InspectorPlugin<W, T> for MetricsPlugin {
+    fn on_schedule(&mut self, _event_id: EventId, _tick: T) {
+        self.scheduled += 1;
+    }
+
+    fn on_execute(&mut self, _event_id: EventId, _tick: T) {
+        self.executed += 1;
+    }
+
+    fn on_cancel(&mut self, _event_id: EventId) {
+        self.cancelled += 1;
+    }
+
+    fn on_tick_end(&mut self, _tick: T, queue_size: usize) {
+        self.ticks += 1;
+        self.peak_queue_size = self.peak_queue_size.max(queue_size);
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimelineEntry<T = u64> {
+    Scheduled { id: EventId, tick: T },
+    Executed { id: EventId, tick: T },
+    Cancelled { id: EventId },
+    TickEnd { tick: T, queue_size: usize },
+}
+
+/// records every hook in the order it was called
+#[derive(Debug, Clone)]
+pub struct TimelinePlugin<T = u64> {
+    entries: Vec<TimelineEntry<T>>,
+}
+
+impl<T> TimelinePlugin<T> {
+    pub fn new() -> Self {
+        Self { entries: Vec::new() }
+    }
+
+    pub fn entries(&self) -> &[TimelineEntry<T>] {
+        &self.entries
+    }
+}
+
+// by hand, since a derive would require `T: Default`
+impl<T> Default for TimelinePlugin<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<W, T: TickTime> InspectorPlugin<W, T> for TimelinePlugin<T> {
+    fn on_schedule(&mut self, id: EventId, tick: T) {
+        self.entries.push(TimelineEntry::Scheduled { id, tick });
+    }
+
+    fn on_execute(&mut self, id: EventId, tick: T) {
+        self.entries.push(TimelineEntry::Executed { id, tick });
+    }
+
+    fn on_cancel(&mut self, id: EventId) {
+        self.entries.push(TimelineEntry::Cancelled { id });
+    }
+
+    fn on_tick_end(&mut self, tick: T, queue_size: usize) {
+        self.entries.push(TimelineEntry::TickEnd { tick, queue_size });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_metrics_plugin_tracks_peak_queue_size() {
+        let mut metrics = MetricsPlugin::new();
+        InspectorPlugin::<(), u64>::on_tick_end(&mut metrics, 1, 4);
+        InspectorPlugin::<(), u64>::on_tick_end(&mut metrics, 2, 2);
+
+        assert_eq!(metrics.ticks, 2);
+        assert_eq!(metrics.peak_queue_size, 4);
+    }
+}
@@ -1,9 +1,67 @@
 mod scheduled_wrapper;
+mod event_queue;
+mod action_event;
+mod chain_event;
+mod recurring_event;
 mod event;
+mod event_ctx;
+mod event_id;
+mod fallible_event;
+mod fn_event;
 mod engine;
 mod scheduler;
+mod execution_record;
+mod execution_order;
+mod error;
+mod handle;
+mod inspector;
+mod metrics;
+mod progress;
+mod quota;
+#[cfg(feature = "serde")]
+mod registry;
+mod queue_snapshot;
+mod checkpoint;
+mod clock;
+mod realtime;
+mod report;
+mod snapshot;
+mod sync_engine;
+mod tick_time;
+#[cfg(feature = "serde")]
+mod state;
+#[cfg(feature = "bench")]
+mod benchmark;
 
 
-pub use event::Event;
+pub use event::{CloneEvent, Event, EventResult};
+pub use event_ctx::EventCtx;
+pub use recurring_event::RecurringEvent;
+pub use event_id::EventId;
+pub use fallible_event::{Fallible, FallibleEvent};
 pub use engine::Engine;
-pub use scheduler::Scheduler;
\ No newline at end of file
+pub use scheduler::Scheduler;
+pub use execution_record::ExecutionRecord;
+pub use execution_order::ExecutionOrder;
+pub use error::{RescheduleError, ScheduleError, SimulationError, StepError};
+#[cfg(feature = "serde")]
+pub use error::RegistryError;
+pub use handle::EventHandle;
+pub use metrics::{EngineMetrics, TypeMetrics};
+pub use inspector::{InspectorPlugin, LoggingPlugin, MetricsPlugin, TimelineEntry, TimelinePlugin};
+pub use scheduled_wrapper::ScheduledEvent;
+#[cfg(feature = "bench")]
+pub use benchmark::BenchmarkResult;
+pub use checkpoint::Checkpointer;
+pub use clock::{MonotonicClock, OverflowPolicy, SimulationClock};
+pub use report::{RunResult, SimulationReport, StepReport, StopReason, TickStats};
+pub use snapshot::EngineSnapshot;
+pub use sync_engine::{SyncEngine, SyncScheduler};
+pub use tick_time::TickTime;
+#[cfg(feature = "serde")]
+pub use state::EngineState;
+pub use quota::EventQuota;
+#[cfg(feature = "serde")]
+pub use registry::{EventRegistry, SavedEvent};
+pub use queue_snapshot::QueueSnapshot;
+pub use progress::{ChannelProgressReporter, PrintProgressReporter, ProgressReporter};
@@ -0,0 +1,31 @@
+use std::collections::HashMap;
+
+/// counts for one event type, see `EngineMetrics`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct TypeMetrics {
+    /// executions since metrics were enabled or last reset
+    pub executed: u64,
+    /// events of this type queued right now
+    pub pending: usize,
+}
+
+/// per-event-type breakdown from `Engine::metrics`, keyed by `Event::type_name`
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct EngineMetrics {
+    pub(crate) per_type: HashMap<&'static str, TypeMetrics>,
+}
+
+impl EngineMetrics {
+    /// the counts for `E`, all zero if none has been seen
+    pub fn of<E: 'static>(&self) -> TypeMetrics {
+        self.get(std::any::type_name::<E>())
+    }
+
+    pub fn get(&self, type_name: &str) -> TypeMetrics {
+        self.per_type.get(type_name).copied().unwrap_or_default()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&'static str, TypeMetrics)> + '_ {
+        self.per_type.iter().map(|(&name, &metrics)| (name, metrics))
+    }
+}
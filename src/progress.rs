@@ -0,0 +1,78 @@
+use std::sync::mpsc::Sender;
+
+pub trait ProgressReporter<T = u64> {
+    fn report(&mut self, current: T, total: T);
+}
+
+/// prints progress to stderr every `interval` ticks (1000 by default). it checks for whole
+/// multiples of `interval`, so it only reports `u64` time; `ChannelProgressReporter` takes any
+pub struct PrintProgressReporter {
+    interval: u64,
+}
+
+impl PrintProgressReporter {
+    pub fn new() -> Self {
+        Self { interval: 1000 }
+    }
+
+    pub fn with_interval(interval: u64) -> Self {
+        Self { interval }
+    }
+}
+
+impl Default for PrintProgressReporter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ProgressReporter for PrintProgressReporter {
+    fn report(&mut self, current: u64, total: u64) {
+        if self.interval != 0 && (current.is_multiple_of(self.interval) || current == total) {
+            eprintln!("tick {}/{}", current, total);
+        }
+    }
+}
+
+/// forwards every `(current, total)` update over a channel; a dropped receiver is ignored
+pub struct ChannelProgressReporter<T = u64> {
+    sender: Sender<(T, T)>,
+}
+
+impl<T> ChannelProgressReporter<T> {
+    pub fn new(sender: Sender<(T, T)>) -> Self {
+        Self { sender }
+    }
+}
+
+impl<T> ProgressReporter<T> for ChannelProgressReporter<T> {
+    fn report(&mut self, current: T, total: T) {
+        let _ = self.sender.send((current, total));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::mpsc;
+
+    #[test]
+    fn test_channel_reporter_forwards_updates() {
+        let (tx, rx) = mpsc::channel();
+        let mut reporter = ChannelProgressReporter::new(tx);
+
+        reporter.report(1, 3);
+        reporter.report(2, 3);
+
+        assert_eq!(rx.try_iter().collect::<Vec<_>>(), vec![(1, 3), (2, 3)]);
+    }
+
+    #[test]
+    fn test_channel_reporter_ignores_dropped_receiver() {
+        let (tx, rx) = mpsc::channel();
+        drop(rx);
+
+        let mut reporter = ChannelProgressReporter::new(tx);
+        reporter.report(1, 1);
+    }
+}
@@ -0,0 +1,29 @@
+use crate::EventId;
+use std::collections::VecDeque;
+
+/// an owned copy of the pending `(tick, id)` pairs in execution order, see `Engine::snapshot_queue`.
+/// iterating consumes it front to back; equality compares the remaining sequence
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct QueueSnapshot<T = u64> {
+    entries: VecDeque<(T, EventId)>,
+}
+
+impl<T> QueueSnapshot<T> {
+    pub(crate) fn new(entries: VecDeque<(T, EventId)>) -> Self {
+        Self { entries }
+    }
+}
+
+impl<T> Iterator for QueueSnapshot<T> {
+    type Item = (T, EventId);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.entries.pop_front()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.entries.len(), Some(self.entries.len()))
+    }
+}
+
+impl<T> ExactSizeIterator for QueueSnapshot<T> {}
@@ -0,0 +1,38 @@
+use std::collections::HashMap;
+
+/// per-tick execution limits for event categories, see `Engine::with_event_quota`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EventQuota {
+    limits: HashMap<&'static str, usize>,
+}
+
+impl EventQuota {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// lets at most `max_per_tick` events tagged `category` execute in a single tick
+    pub fn with_limit(mut self, category: &'static str, max_per_tick: usize) -> Self {
+        self.limits.insert(category, max_per_tick);
+
+        self
+    }
+
+    pub fn limit(&self, category: &str) -> Option<usize> {
+        self.limits.get(category).copied()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_limits_are_per_category() {
+        let quota = EventQuota::new().with_limit("ai", 2).with_limit("combat", 5);
+
+        assert_eq!(quota.limit("ai"), Some(2));
+        assert_eq!(quota.limit("combat"), Some(5));
+        assert_eq!(quota.limit("physics"), None);
+    }
+}
@@ -0,0 +1,59 @@
+use std::time::Instant;
+
+// accumulates wall time into whole ticks for `Engine::poll_realtime`
+pub(crate) struct SoftRealtime {
+    target_tps: f64,
+    max_catchup_ticks: u64,
+    last_wall_clock: Instant,
+    accumulated_ticks: f64,
+}
+
+impl SoftRealtime {
+    pub(crate) fn new(target_tps: f64, max_catchup_ticks: u64) -> Self {
+        Self {
+            target_tps,
+            max_catchup_ticks,
+            last_wall_clock: Instant::now(),
+            accumulated_ticks: 0.0,
+        }
+    }
+
+    // whole ticks owed since the last call, never more than one plus the catch-up allowance
+    pub(crate) fn due_ticks(&mut self, now: Instant) -> u64 {
+        let elapsed = now.saturating_duration_since(self.last_wall_clock);
+        self.last_wall_clock = now;
+
+        let max_ticks = (self.max_catchup_ticks + 1) as f64;
+        self.accumulated_ticks =
+            (self.accumulated_ticks + elapsed.as_secs_f64() * self.target_tps).min(max_ticks);
+
+        let due = self.accumulated_ticks.floor();
+        self.accumulated_ticks -= due;
+        due as u64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_due_ticks_accumulates_fractions() {
+        let mut realtime = SoftRealtime::new(10.0, 5);
+        let start = realtime.last_wall_clock;
+
+        assert_eq!(realtime.due_ticks(start + Duration::from_millis(150)), 1);
+        assert_eq!(realtime.due_ticks(start + Duration::from_millis(200)), 1);
+        assert_eq!(realtime.due_ticks(start + Duration::from_millis(210)), 0);
+    }
+
+    #[test]
+    fn test_due_ticks_caps_catch_up() {
+        let mut realtime = SoftRealtime::new(10.0, 2);
+        let start = realtime.last_wall_clock;
+
+        // ten seconds behind, but only 1 + 2 catch-up ticks are allowed
+        assert_eq!(realtime.due_ticks(start + Duration::from_secs(10)), 3);
+    }
+}
@@ -0,0 +1,113 @@
+use crate::{Event, EventCtx, EventResult, TickTime};
+
+/// runs `inner` and then queues itself again `period` ticks later, optionally only `stop_after`
+/// times in total. it stays the same queue entry throughout, so a handle to it can cancel the
+/// remaining runs. the period takes over from any `reschedule_self` by `inner`
+pub struct RecurringEvent<W, T: TickTime = u64> {
+    inner: Box<dyn Event<W, T>>,
+    period: T,
+    remaining: Option<u64>,
+}
+
+impl<W: 'static, T: TickTime> RecurringEvent<W, T> {
+    /// panics if `period` is 0
+    pub fn new(inner: Box<dyn Event<W, T>>, period: T) -> Self {
+        assert!(period > T::ZERO, "recurring events need a period of at least one tick");
+
+        Self {
+            inner,
+            period,
+            remaining: None,
+        }
+    }
+
+    /// stops recurring after `runs` executions; 0 makes the event a no-op
+    pub fn stop_after(mut self, runs: u64) -> Self {
+        self.remaining = Some(runs);
+
+        self
+    }
+}
+
+impl<W: 'static, T: TickTime> Event<W, T> for RecurringEvent<W, T> {
+    fn execute(&mut self, world: &mut W, ctx: &mut EventCtx<W, T>) -> EventResult {
+        if self.remaining == Some(0) {
+            return EventResult::Continue;
+        }
+
+        let result = self.inner.execute(world, ctx);
+
+        self.remaining = self.remaining.map(|runs| runs - 1);
+        if self.remaining != Some(0) {
+            ctx.reschedule_self(self.period);
+        }
+
+        result
+    }
+
+    fn on_cancel(&self, world: &mut W, current_tick: T) {
+        self.inner.on_cancel(world, current_tick);
+    }
+
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    fn priority(&self) -> i32 {
+        self.inner.priority()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Engine;
+
+    struct Tally;
+
+    impl Event<Vec<u64>> for Tally {
+        fn execute(&mut self, ticks: &mut Vec<u64>, ctx: &mut EventCtx<Vec<u64>>) -> EventResult {
+            ticks.push(ctx.tick());
+            EventResult::Continue
+        }
+    }
+
+    #[test]
+    fn test_recurring_event_repeats_every_period() {
+        let mut ticks = Vec::new();
+        let mut engine = Engine::build();
+        engine.schedule(Box::new(RecurringEvent::new(Box::new(Tally), 5)), 5);
+
+        engine.step_until(20, &mut ticks);
+
+        assert_eq!(ticks, vec![5, 10, 15, 20]);
+        assert_eq!(engine.get_queue_size(), 1);
+    }
+
+    #[test]
+    fn test_recurring_event_stops_after_n_runs() {
+        let mut ticks = Vec::new();
+        let mut engine = Engine::build();
+        engine.schedule(Box::new(RecurringEvent::new(Box::new(Tally), 3).stop_after(2)), 1);
+        engine.schedule(Box::new(RecurringEvent::new(Box::new(Tally), 3).stop_after(0)), 1);
+
+        engine.step_until(20, &mut ticks);
+
+        assert_eq!(ticks, vec![1, 4]);
+        assert_eq!(engine.get_queue_size(), 0);
+    }
+
+    #[test]
+    fn test_recurring_event_keeps_its_handle() {
+        let mut ticks = Vec::new();
+        let mut engine = Engine::build();
+        let handle = engine.schedule(Box::new(RecurringEvent::new(Box::new(Tally), 4)), 2);
+
+        engine.step_until(10, &mut ticks);
+        assert_eq!(handle.get_tick(&engine), Some(14));
+
+        assert!(handle.cancel(&mut engine));
+        engine.step_until(20, &mut ticks);
+        assert_eq!(ticks, vec![2, 6, 10]);
+    }
+}
@@ -0,0 +1,158 @@
+use crate::Event;
+use crate::RegistryError;
+use serde::{Deserialize, Serialize};
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+
+type SerializeFn = fn(&dyn Any) -> serde_json::Result<Value>;
+type DeserializeFn<W> = fn(Value) -> serde_json::Result<Box<dyn Event<W>>>;
+
+/// one pending event as saved by `Engine::save`: its registered type name and its data
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SavedEvent {
+    pub type_name: String,
+    pub data: Value,
+}
+
+/// maps event types to and from their serialized form so queues holding several event types can
+/// be saved with `Engine::save` and brought back with `Engine::restore`. every type queued at
+/// save time has to be registered, under its `std::any::type_name`
+pub struct EventRegistry<W> {
+    serializers: HashMap<TypeId, (&'static str, SerializeFn)>,
+    deserializers: HashMap<&'static str, DeserializeFn<W>>,
+}
+
+impl<W: 'static> EventRegistry<W> {
+    pub fn new() -> Self {
+        Self {
+            serializers: HashMap::new(),
+            deserializers: HashMap::new(),
+        }
+    }
+
+    pub fn register<E>(mut self) -> Self
+    where
+        E: Event<W> + Serialize + DeserializeOwned,
+    {
+        let name = std::any::type_name::<E>();
+        self.serializers.insert(TypeId::of::<E>(), (name, serialize_as::<E>));
+        self.deserializers.insert(name, deserialize_as::<W, E>);
+
+        self
+    }
+
+    pub(crate) fn save(&self, event: &dyn Event<W>) -> Result<SavedEvent, RegistryError> {
+        let event: &dyn Any = event;
+        let Some(&(name, serialize)) = self.serializers.get(&event.type_id()) else {
+            return Err(RegistryError::Unregistered);
+        };
+
+        let data = serialize(event).map_err(|err| RegistryError::Serde(err.to_string()))?;
+        Ok(SavedEvent { type_name: name.to_string(), data })
+    }
+
+    pub(crate) fn load(&self, saved: SavedEvent) -> Result<Box<dyn Event<W>>, RegistryError> {
+        let Some(deserialize) = self.deserializers.get(saved.type_name.as_str()) else {
+            return Err(RegistryError::UnknownTypeName(saved.type_name));
+        };
+
+        deserialize(saved.data).map_err(|err| RegistryError::Serde(err.to_string()))
+    }
+}
+
+impl<W: 'static> Default for EventRegistry<W> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn serialize_as<E: Serialize + 'static>(event: &dyn Any) -> serde_json::Result<Value> {
+    // only ever called with the `TypeId` it was registered under
+    serde_json::to_value(event.downcast_ref::<E>().expect("registered type id"))
+}
+
+fn deserialize_as<W, E>(data: Value) -> serde_json::Result<Box<dyn Event<W>>>
+where
+    E: Event<W> + DeserializeOwned,
+{
+    Ok(Box::new(serde_json::from_value::<E>(data)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Engine, EventCtx, EventResult};
+
+    #[derive(Serialize, Deserialize)]
+    struct Harvest {
+        crop: String,
+    }
+
+    impl Event<Vec<String>> for Harvest {
+        fn execute(&mut self, log: &mut Vec<String>, ctx: &mut EventCtx<Vec<String>>) -> EventResult {
+            let tick = ctx.tick();
+            log.push(format!("{}: {}", tick, self.crop));
+            ctx.scheduler().schedule_with_priority(Box::new(Rain { inches: tick % 3 }), 2, -1);
+            ctx.scheduler().schedule(Box::new(Harvest { crop: self.crop.clone() }), 7);
+
+            EventResult::Continue
+        }
+    }
+
+    #[derive(Serialize, Deserialize)]
+    struct Rain {
+        inches: u64,
+    }
+
+    impl Event<Vec<String>> for Rain {
+        fn execute(&mut self, log: &mut Vec<String>, ctx: &mut EventCtx<Vec<String>>) -> EventResult {
+            log.push(format!("{}: rain {}", ctx.tick(), self.inches));
+            EventResult::Continue
+        }
+    }
+
+    fn farm() -> Engine<Vec<String>> {
+        let mut engine = Engine::build();
+        engine.schedule(Box::new(Harvest { crop: "wheat".into() }), 1);
+        engine.schedule(Box::new(Harvest { crop: "corn".into() }), 3);
+        engine.schedule_repeating(Box::new(Rain { inches: 1 }), 4, 5, None);
+        engine
+    }
+
+    #[test]
+    fn test_restore_resumes_the_original_execution_order() {
+        let registry = EventRegistry::new().register::<Harvest>().register::<Rain>();
+
+        let mut original = farm();
+        original.step_until(50, &mut Vec::new());
+        let json = serde_json::to_string(&original.save(&registry).unwrap()).unwrap();
+
+        let mut expected = Vec::new();
+        original.step_until(100, &mut expected);
+
+        let mut restored = Engine::build();
+        restored.restore(serde_json::from_str(&json).unwrap(), &registry).unwrap();
+        let mut replayed = Vec::new();
+        restored.step_until(100, &mut replayed);
+
+        assert!(!expected.is_empty());
+        assert_eq!(replayed, expected);
+    }
+
+    #[test]
+    fn test_unregistered_types_are_reported() {
+        let partial = EventRegistry::new().register::<Harvest>();
+
+        assert_eq!(farm().save(&partial).unwrap_err(), RegistryError::Unregistered);
+
+        let full = EventRegistry::new().register::<Harvest>().register::<Rain>();
+        let state = farm().save(&full).unwrap();
+        let mut engine = Engine::build();
+        assert!(matches!(
+            engine.restore(state, &partial),
+            Err(RegistryError::UnknownTypeName(_))
+        ));
+    }
+}
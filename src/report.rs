@@ -0,0 +1,56 @@
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct SimulationReport {
+    pub ticks_advanced: u64,
+    pub total_events_executed: u64,
+    /// events executed in each tick of the run, index 0 being the first tick advanced
+    pub events_per_tick: Vec<u64>,
+    pub final_queue_size: usize,
+    /// true if the queue drained before all requested ticks were run
+    pub stopped_early: bool,
+}
+
+/// what a single `step` did
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct StepReport<T = u64> {
+    /// the tick that was stepped to
+    pub tick: T,
+    pub executed: u64,
+    /// events that were due this tick but were left over by `max_executions_per_tick`
+    /// or pushed to the next tick by an `EventQuota`
+    pub deferred: u64,
+    pub queue_len_after: usize,
+    /// an event halted the engine during this step, or it was already halted
+    pub halted: bool,
+}
+
+/// how loaded the last tick was, see `Engine::last_tick_stats`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct TickStats<T = u64> {
+    pub tick: T,
+    pub events_executed: u64,
+    /// events that were due this tick but were left over once `max_executions_per_tick` was
+    /// reached. they keep their original tick and priority, so they run first next tick, ahead of
+    /// that tick's own events however those are prioritised
+    pub events_skipped_by_limit: u64,
+    pub queue_depth_after: usize,
+}
+
+/// why `Engine::run_until` returned
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StopReason {
+    Predicate,
+    TickLimit,
+    QueueEmpty,
+    /// an event called `Scheduler::halt`
+    Halted,
+}
+
+/// what a `run_until` call did
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RunResult<T = u64> {
+    pub reason: StopReason,
+    /// the tick the run ended at
+    pub tick: T,
+    pub ticks_run: u64,
+    pub events_executed: u64,
+}
@@ -1,27 +1,109 @@
 use crate::Event;
+use crate::EventId;
+use crate::TickTime;
+use std::any::{Any, TypeId};
+use std::borrow::Borrow;
+use std::collections::HashMap;
+use std::fmt;
 use std::hash::{Hash, Hasher};
-pub struct ScheduledEvent<W> {
-    pub id: u64,
-    pub event: Box<dyn Event<W>>,
+
+pub struct ScheduledEvent<W, T: TickTime = u64> {
+    pub id: EventId,
+    pub event: Box<dyn Event<W, T>>,
+    pub metadata: HashMap<String, String>,
+    /// id of the event whose execution scheduled this one, `None` for top-level schedules
+    pub spawned_by: Option<EventId>,
+    /// 0 for top-level schedules, otherwise one more than the spawning event's depth
+    pub spawn_depth: u32,
+    /// `EventQuota` category, set by `schedule_tagged` and matched by `Engine::cancel_by_tag`
+    pub tag: Option<&'static str>,
+    /// same-tick priority from `schedule_with_priority`, lower fires first
+    pub priority: i32,
+    pub(crate) repeat: Option<Repeat<T>>,
+}
+
+// set by `schedule_repeating`: the engine re-queues the event `interval` ticks after each run
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub(crate) struct Repeat<T = u64> {
+    pub(crate) interval: T,
+    // runs left including the next one, `None` for forever
+    pub(crate) remaining: Option<u64>,
+}
+
+impl<T: Copy> Repeat<T> {
+    // the schedule after one more run, `None` once it is used up
+    pub(crate) fn next(self) -> Option<Repeat<T>> {
+        match self.remaining {
+            None => Some(self),
+            Some(n) if n > 1 => Some(Repeat { remaining: Some(n - 1), ..self }),
+            Some(_) => None,
+        }
+    }
+}
+
+impl<W, T: TickTime> ScheduledEvent<W, T> {
+    pub(crate) fn new(id: EventId, event: Box<dyn Event<W, T>>) -> Self {
+        Self {
+            id,
+            event,
+            metadata: HashMap::new(),
+            spawned_by: None,
+            spawn_depth: 0,
+            tag: None,
+            priority: 0,
+            repeat: None,
+        }
+    }
+}
+
+impl<W: 'static, T: TickTime> ScheduledEvent<W, T> {
+    /// `Event::name` of the event inside the box
+    pub fn name(&self) -> &str {
+        self.event.name()
+    }
+
+    /// `TypeId` of the concrete event inside the box
+    pub fn event_type_id(&self) -> TypeId {
+        let event: &dyn Any = &*self.event;
+        event.type_id()
+    }
 }
 
-impl<W> Hash for ScheduledEvent<W> {
+impl<W, T: TickTime> Hash for ScheduledEvent<W, T> {
     fn hash<H: Hasher>(&self, state: &mut H) {
         self.id.hash(state);
     }
 }
 
-impl<W> PartialEq for ScheduledEvent<W> {
+impl<W, T: TickTime> PartialEq for ScheduledEvent<W, T> {
     fn eq(&self, other: &Self) -> bool {
         self.id == other.id
     }
 }
 
-impl<W> Eq for ScheduledEvent<W> {}
+impl<W, T: TickTime> Eq for ScheduledEvent<W, T> {}
+
+// lets the queue be searched by id alone; hash and eq already only use the id
+impl<W, T: TickTime> Borrow<EventId> for ScheduledEvent<W, T> {
+    fn borrow(&self) -> &EventId {
+        &self.id
+    }
+}
+
+impl<W: 'static, T: TickTime> fmt::Debug for ScheduledEvent<W, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ScheduledEvent")
+            .field("id", &self.id)
+            .field("event", &self.event.type_name())
+            .field("name", &self.name())
+            .finish_non_exhaustive()
+    }
+}
 
 #[cfg(test)]
 mod tests {
-    use crate::Scheduler;
+    use crate::{EventCtx, EventResult};
 
     use super::*;
 
@@ -29,44 +111,51 @@ mod tests {
     struct MockEvent;
     
     impl Event<()> for MockEvent {
-        fn execute(&self, _world: &mut (), _current_tick: u64, _scheduler: &mut Scheduler<()>) {
+        fn execute(&mut self, _world: &mut (), _ctx: &mut EventCtx<()>) -> EventResult {
+            EventResult::Continue
         }
     }
 
     #[test]
     fn test_scheduled_event_equality_same_id() {
-        let event1 = ScheduledEvent {
-            id: 42,
-            event: Box::new(MockEvent),
-        };
-        let event2 = ScheduledEvent {
-            id: 42,
-            event: Box::new(MockEvent),
-        };
+        let event1 = ScheduledEvent::new(EventId(42), Box::new(MockEvent));
+        let event2 = ScheduledEvent::new(EventId(42), Box::new(MockEvent));
 
         assert!(event1 == event2);
     }
 
     #[test]
     fn test_scheduled_event_inequality_different_id() {
-        let event1 = ScheduledEvent {
-            id: 42,
-            event: Box::new(MockEvent),
-        };
-        let event2 = ScheduledEvent {
-            id: 100,
-            event: Box::new(MockEvent),
-        };
+        let event1 = ScheduledEvent::new(EventId(42), Box::new(MockEvent));
+        let event2 = ScheduledEvent::new(EventId(100), Box::new(MockEvent));
 
         assert!(event1 != event2);
     }
 
+    #[test]
+    fn test_scheduled_event_lookup_by_id() {
+        use priority_queue::PriorityQueue;
+
+        let mut queue = PriorityQueue::new();
+        queue.push(
+            ScheduledEvent::new(EventId(7), Box::new(MockEvent) as Box<dyn Event<()>>),
+            3,
+        );
+
+        assert_eq!(queue.get_priority(&EventId(7)), Some(&3));
+        assert!(queue.get_priority(&EventId(8)).is_none());
+    }
+
+    #[test]
+    fn test_event_type_id_sees_through_box() {
+        let event = ScheduledEvent::new(EventId(1), Box::new(MockEvent) as Box<dyn Event<()>>);
+
+        assert_eq!(event.event_type_id(), TypeId::of::<MockEvent>());
+    }
+
     #[test]
     fn test_scheduled_event_eq_reflexive() {
-        let event = ScheduledEvent {
-            id: 42,
-            event: Box::new(MockEvent),
-        };
+        let event = ScheduledEvent::new(EventId(42), Box::new(MockEvent));
 
         assert!(event == event);
     }
@@ -1,22 +1,490 @@
-use priority_queue::PriorityQueue;
+use rand::rngs::StdRng;
+use std::any::TypeId;
 use std::cmp::Reverse;
-use crate::scheduled_wrapper::ScheduledEvent;
+use std::collections::HashMap;
+use std::fmt;
+use crate::event_queue::{EventQueue, QueueKey};
+use crate::fn_event::FnEvent;
+use crate::scheduled_wrapper::{Repeat, ScheduledEvent};
 use crate::Event;
+use crate::EventHandle;
+use crate::EventId;
+use crate::ExecutionOrder;
+use crate::InspectorPlugin;
+use crate::OverflowPolicy;
+use crate::RescheduleError;
+use crate::ScheduleError;
+use crate::TickTime;
 
-pub struct Scheduler<'a, W> {
-    pub current_tick: u64,
-    pub queue: &'a mut PriorityQueue<ScheduledEvent<W>, Reverse<(u64, u64)>>,
+pub struct Scheduler<'a, W, T: TickTime = u64> {
+    pub current_tick: T,
+    pub(crate) queue: &'a mut EventQueue<W, T>,
     pub id_counter: &'a mut u64,
+    pub(crate) id_step: u64,
+    pub(crate) debug_asserts: bool,
+    pub(crate) type_limits: &'a HashMap<TypeId, usize>,
+    pub(crate) current_executing_id: Option<EventId>,
+    pub(crate) child_spawn_depth: u32,
+    pub(crate) execution_order: ExecutionOrder,
+    pub(crate) order_rng: &'a mut StdRng,
+    pub(crate) plugins: &'a mut [Box<dyn InspectorPlugin<W, T>>],
+    pub(crate) backoff: Option<&'a dyn Fn(usize) -> T>,
+    pub(crate) total_events_scheduled: &'a mut u64,
+    pub(crate) halted: &'a mut bool,
+    pub(crate) rng: &'a mut StdRng,
+    pub(crate) overflow_policy: OverflowPolicy,
 }
 
-impl<'a, W> Scheduler<'a, W> {
-    pub fn schedule(&mut self, event: Box<dyn Event<W>>, delay: u64) {
-        *self.id_counter += 1;
-        let id = *self.id_counter;
+impl<'a, W: 'static, T: TickTime> Scheduler<'a, W, T> {
+    /// schedules `event` after `delay` ticks and returns a handle to it. if a type limit rejects
+    /// the event it is dropped and the handle never fires - use `try_schedule` to observe that
+    pub fn schedule(&mut self, event: Box<dyn Event<W, T>>, delay: T) -> EventHandle<W, T> {
+        self.schedule_with_priority(event, delay, 0)
+    }
+
+    /// schedules the closure `f` as a one-off event after `delay` ticks, for actions too small
+    /// to be worth an `Event` type. it gets the world, the tick and this scheduler
+    pub fn schedule_fn<F>(&mut self, f: F, delay: T) -> EventHandle<W, T>
+    where
+        F: FnMut(&mut W, T, &mut Scheduler<'_, W, T>) + 'static,
+    {
+        self.schedule(Box::new(FnEvent::new(f)), delay)
+    }
+
+    /// like `schedule`, but among events due at the same tick lower `priority` fires first.
+    /// `schedule` uses 0; `ExecutionOrder` only breaks ties between equal priorities
+    pub fn schedule_with_priority(
+        &mut self,
+        event: Box<dyn Event<W, T>>,
+        delay: T,
+        priority: i32,
+    ) -> EventHandle<W, T> {
+        self.schedule_or_drop(event, delay, None, priority, None)
+    }
+
+    pub fn try_schedule(&mut self, event: Box<dyn Event<W, T>>, delay: T) -> Result<EventId, ScheduleError> {
+        let tick = self.due_tick(delay)?;
+        self.push_at(event, tick, None, 0, None)
+    }
+
+    /// schedules `event` at the absolute `tick`, which must be after `current_tick`.
+    /// backoff does not apply
+    pub fn schedule_at(&mut self, event: Box<dyn Event<W, T>>, tick: T) -> Result<EventHandle<W, T>, ScheduleError> {
+        if tick <= self.current_tick {
+            return Err(ScheduleError::TickInPast);
+        }
+
+        let id = self.push_at(event, tick, None, 0, None)?;
+        Ok(EventHandle::new(id, tick))
+    }
+
+    /// schedules `event` after `first_delay` ticks and then every `interval` ticks, `repeats`
+    /// times in total or forever if `None`. it keeps the same id throughout, so the handle can
+    /// cancel the remaining runs. `Some(0)` schedules nothing. panics if `interval` is 0
+    pub fn schedule_repeating(
+        &mut self,
+        event: Box<dyn Event<W, T>>,
+        first_delay: T,
+        interval: T,
+        repeats: Option<u64>,
+    ) -> EventHandle<W, T> {
+        assert!(interval > T::ZERO, "repeating events need an interval of at least one tick");
+
+        if repeats == Some(0) {
+            // an id of its own, so the handle can't reach whichever event was scheduled last
+            let tick = self.due_tick(first_delay).unwrap_or(T::MAX);
+            return EventHandle::new(self.next_id(), tick);
+        }
+
+        let repeat = Repeat { interval, remaining: repeats };
+        self.schedule_or_drop(event, first_delay, None, 0, Some(repeat))
+    }
+
+    /// like `schedule`, but the event counts against `tag`'s `EventQuota` when it executes
+    pub fn schedule_tagged(
+        &mut self,
+        event: Box<dyn Event<W, T>>,
+        delay: T,
+        tag: &'static str,
+    ) -> EventHandle<W, T> {
+        self.schedule_or_drop(event, delay, Some(tag), 0, None)
+    }
+
+    /// schedules `event` `additional_delay` ticks after the pending event `base_id` fires
+    pub fn schedule_relative_to(
+        &mut self,
+        event: Box<dyn Event<W, T>>,
+        base_id: EventId,
+        additional_delay: T,
+    ) -> Result<EventId, ScheduleError> {
+        let Some(&Reverse(QueueKey { tick: base_tick, .. })) = self.queue.get_priority(&base_id) else {
+            return Err(ScheduleError::BaseEventNotFound);
+        };
+
+        let tick = self.tick_after(base_tick, additional_delay)?;
+        self.push_at(event, tick, None, 0, None)
+    }
+
+    /// removes the pending event `id`, returning false if it already fired or never existed
+    pub fn cancel(&mut self, id: EventId) -> bool {
+        if self.queue.remove(&id).is_none() {
+            return false;
+        }
+
+        #[cfg(feature = "tracing")]
+        tracing::debug!(id = id.as_u64(), "cancelled");
+
+        for plugin in self.plugins.iter_mut() {
+            plugin.on_cancel(id);
+        }
+        true
+    }
+
+    /// moves the pending event `id` to `current_tick + new_delay`, keeping its id and its place
+    /// among events at that tick. returns false if it is not queued or the new tick overflows
+    pub fn reschedule(&mut self, id: EventId, new_delay: T) -> bool {
+        self.try_reschedule(id, new_delay).is_ok()
+    }
+
+    pub(crate) fn try_reschedule(&mut self, id: EventId, new_delay: T) -> Result<(), RescheduleError> {
+        let Some(&Reverse(key)) = self.queue.get_priority(&id) else {
+            return Err(RescheduleError::NotFound);
+        };
+
+        let tick = self
+            .tick_after(self.current_tick, new_delay)
+            .map_err(|_| RescheduleError::TickOverflow)?;
+        self.queue.change_priority(&id, Reverse(QueueKey { tick, ..key }));
+
+        #[cfg(feature = "tracing")]
+        tracing::trace!(%tick, from = %key.tick, id = id.as_u64(), "rescheduled");
+
+        Ok(())
+    }
+
+    /// how many pending events are due at exactly `tick`
+    pub fn get_pending_count_for_tick(&self, tick: T) -> usize {
+        self.queue.count_at(tick)
+    }
+
+    /// the engine's rng, seeded by `Engine::rng_seed`. drawing from it instead of `thread_rng`
+    /// keeps runs with the same seed identical
+    pub fn rng(&mut self) -> &mut StdRng {
+        self.rng
+    }
+
+    /// stops the engine once the current event returns. events still due this tick stay queued,
+    /// and stepping does nothing until `Engine::resume` is called
+    pub fn halt(&mut self) {
+        *self.halted = true;
+    }
+
+    // extra delay from `Engine::with_event_backoff` for the current queue depth
+    fn backoff_delay(&self) -> T {
+        self.backoff.map_or(T::ZERO, |backoff| backoff(self.queue.len()))
+    }
+
+    // `tick + delay` under the engine's `OverflowPolicy`
+    fn tick_after(&self, tick: T, delay: T) -> Result<T, ScheduleError> {
+        self.overflow_policy.add_ticks(tick, delay).ok_or(ScheduleError::TickOverflow)
+    }
+
+    // the tick an event scheduled `delay` from now lands on, backoff included
+    fn due_tick(&self, delay: T) -> Result<T, ScheduleError> {
+        let tick = self.tick_after(self.current_tick, delay)?;
+        self.tick_after(tick, self.backoff_delay())
+    }
+
+    // for the schedule calls that hand back a handle rather than a `Result`. an overflow panics
+    // under `OverflowPolicy::Panic`; otherwise a rejected event is dropped and the handle never fires
+    fn schedule_or_drop(
+        &mut self,
+        event: Box<dyn Event<W, T>>,
+        delay: T,
+        tag: Option<&'static str>,
+        priority: i32,
+        repeat: Option<Repeat<T>>,
+    ) -> EventHandle<W, T> {
+        let tick = match self.due_tick(delay) {
+            Ok(tick) => tick,
+            Err(err) if self.overflow_policy == OverflowPolicy::Panic => {
+                panic!("{}: {} ticks after {}", err, delay, self.current_tick)
+            }
+            Err(_err) => {
+                #[cfg(feature = "tracing")]
+                tracing::debug!(%delay, event = event.name(), error = %_err, "dropped");
+
+                return EventHandle::new(self.next_id(), T::MAX);
+            }
+        };
+
+        let id = match self.push_at(event, tick, tag, priority, repeat) {
+            Ok(id) => id,
+            Err(_err) => {
+                #[cfg(feature = "tracing")]
+                tracing::debug!(%tick, id = *self.id_counter, error = %_err, "dropped");
+
+                EventId(*self.id_counter)
+            }
+        };
+
+        EventHandle::new(id, tick)
+    }
+
+    fn push_at(
+        &mut self,
+        event: Box<dyn Event<W, T>>,
+        tick: T,
+        tag: Option<&'static str>,
+        priority: i32,
+        repeat: Option<Repeat<T>>,
+    ) -> Result<EventId, ScheduleError> {
+        let id = self.next_id();
+
+        let mut item = ScheduledEvent::new(id, event);
+        item.spawned_by = self.current_executing_id;
+        item.spawn_depth = self.child_spawn_depth;
+        item.tag = tag;
+        item.priority = priority;
+        item.repeat = repeat;
+        if self.at_type_limit(&item) {
+            return Err(ScheduleError::TypeLimitReached);
+        }
+
+        Ok(self.enqueue(item, tick))
+    }
+
+    fn next_id(&mut self) -> EventId {
+        *self.id_counter += self.id_step;
+        EventId(*self.id_counter)
+    }
+
+    // queues an already built item, e.g. a repeating event going round again
+    pub(crate) fn enqueue(&mut self, item: ScheduledEvent<W, T>, tick: T) -> EventId {
+        let id = item.id;
+        let order = self.execution_order.order_key(id, &*item.event, self.order_rng);
+        let key = Reverse(QueueKey { tick, priority: item.priority, order, id });
+
+        #[cfg(feature = "tracing")]
+        tracing::trace!(%tick, event = item.name(), id = id.as_u64(), "scheduled");
+
+        let replaced = self.queue.push(item, key);
+        if self.debug_asserts {
+            assert!(replaced.is_none(), "event id {} was scheduled twice", id);
+        }
+
+        *self.total_events_scheduled += 1;
+        for plugin in self.plugins.iter_mut() {
+            plugin.on_schedule(id, tick);
+        }
+
+        id
+    }
+
+    fn at_type_limit(&self, item: &ScheduledEvent<W, T>) -> bool {
+        let type_id = item.event_type_id();
+        let Some(&max) = self.type_limits.get(&type_id) else {
+            return false;
+        };
 
-        let item = ScheduledEvent { id, event };
-        let priority = Reverse((self.current_tick + delay, id));
+        let pending = self
+            .queue
+            .iter()
+            .filter(|(queued, _)| queued.event_type_id() == type_id)
+            .count();
+        pending >= max
+    }
+}
+
+impl<W, T: TickTime> fmt::Debug for Scheduler<'_, W, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Scheduler")
+            .field("current_tick", &self.current_tick)
+            .field("current_executing_id", &self.current_executing_id)
+            .field("queue_len", &self.queue.len())
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Engine, Event, EventCtx, EventId, EventResult, ScheduleError};
+
+    struct TestWorld {
+        fired: Vec<(u64, &'static str)>,
+    }
 
-        self.queue.push(item, priority);
+    struct Named(&'static str);
+
+    impl Event<TestWorld> for Named {
+        fn execute(&mut self, world: &mut TestWorld, ctx: &mut EventCtx<TestWorld>) -> EventResult {
+            world.fired.push((ctx.tick(), self.0));
+            EventResult::Continue
+        }
+    }
+
+    // schedules a follow-up relative to a sibling it scheduled first
+    struct Chain;
+
+    impl Event<TestWorld> for Chain {
+        fn execute(&mut self, _world: &mut TestWorld, ctx: &mut EventCtx<TestWorld>) -> EventResult {
+            let base = ctx.scheduler().schedule(Box::new(Named("base")), 4);
+            ctx.scheduler()
+                .schedule_relative_to(Box::new(Named("follow-up")), base.id(), 2)
+                .unwrap();
+
+            assert_eq!(
+                ctx.scheduler().schedule_relative_to(Box::new(Named("orphan")), EventId(999), 1),
+                Err(ScheduleError::BaseEventNotFound)
+            );
+
+            EventResult::Continue
+        }
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_schedule_relative_to_pending_event() {
+        let mut world = TestWorld { fired: vec![] };
+        let mut engine = Engine::build();
+        engine.schedule(Box::new(Chain), 1);
+
+        engine.step_until(10, &mut world);
+
+        assert_eq!(world.fired, vec![(5, "base"), (7, "follow-up")]);
+    }
+
+    // schedules two siblings, then thinks better of the first
+    struct ChangeOfHeart;
+
+    impl Event<TestWorld> for ChangeOfHeart {
+        fn execute(&mut self, _world: &mut TestWorld, ctx: &mut EventCtx<TestWorld>) -> EventResult {
+            let regret = ctx.scheduler().schedule(Box::new(Named("regret")), 1);
+            ctx.scheduler().schedule(Box::new(Named("keeper")), 1);
+
+            assert!(ctx.scheduler().cancel(regret.id()));
+            assert!(!ctx.scheduler().cancel(regret.id()));
+
+            EventResult::Continue
+        }
+    }
+
+    #[test]
+    fn test_cancel_sibling_from_execute() {
+        let mut world = TestWorld { fired: vec![] };
+        let mut engine = Engine::build();
+        engine.schedule(Box::new(ChangeOfHeart), 1);
+
+        engine.step_until(3, &mut world);
+
+        assert_eq!(world.fired, vec![(2, "keeper")]);
+    }
+
+    // refreshes a buff by pushing its existing expiry back instead of queueing another
+    struct Refresh {
+        expiry: EventId,
+    }
+
+    impl Event<TestWorld> for Refresh {
+        fn execute(&mut self, _world: &mut TestWorld, ctx: &mut EventCtx<TestWorld>) -> EventResult {
+            assert!(ctx.scheduler().reschedule(self.expiry, 5));
+            assert!(!ctx.scheduler().reschedule(EventId(999), 5));
+
+            EventResult::Continue
+        }
+    }
+
+    #[test]
+    fn test_reschedule_from_execute() {
+        let mut world = TestWorld { fired: vec![] };
+        let mut engine = Engine::build();
+        let expiry = engine.schedule(Box::new(Named("expired")), 4).id();
+        engine.schedule(Box::new(Refresh { expiry }), 3);
+
+        engine.step_until(10, &mut world);
+
+        assert_eq!(world.fired, vec![(8, "expired")]);
+    }
+
+    // an ai system that happens to queue its attack before its movement
+    struct Plan;
+
+    impl Event<TestWorld> for Plan {
+        fn execute(&mut self, _world: &mut TestWorld, ctx: &mut EventCtx<TestWorld>) -> EventResult {
+            ctx.scheduler().schedule_with_priority(Box::new(Named("attack")), 1, 10);
+            ctx.scheduler().schedule(Box::new(Named("idle")), 1);
+            ctx.scheduler().schedule_with_priority(Box::new(Named("movement")), 1, -10);
+
+            EventResult::Continue
+        }
+    }
+
+    #[test]
+    fn test_schedule_with_priority_runs_lower_values_first() {
+        let mut world = TestWorld { fired: vec![] };
+        let mut engine = Engine::build();
+        engine.schedule(Box::new(Plan), 1);
+
+        engine.step_until(2, &mut world);
+
+        assert_eq!(world.fired, vec![(2, "movement"), (2, "idle"), (2, "attack")]);
+    }
+
+    // books a follow-up at an absolute tick rather than a delay
+    struct Appointment;
+
+    impl Event<TestWorld> for Appointment {
+        fn execute(&mut self, _world: &mut TestWorld, ctx: &mut EventCtx<TestWorld>) -> EventResult {
+            let now = ctx.tick();
+            ctx.scheduler().schedule_at(Box::new(Named("appointment")), 6).unwrap();
+            assert_eq!(
+                ctx.scheduler().schedule_at(Box::new(Named("too late")), now).unwrap_err(),
+                ScheduleError::TickInPast
+            );
+
+            EventResult::Continue
+        }
+    }
+
+    #[test]
+    fn test_schedule_at_from_execute() {
+        let mut world = TestWorld { fired: vec![] };
+        let mut engine = Engine::build();
+        engine.schedule(Box::new(Appointment), 2);
+
+        engine.step_until(10, &mut world);
+
+        assert_eq!(world.fired, vec![(6, "appointment")]);
+    }
+
+    // places each child on the least crowded of the next three ticks
+    struct Spread;
+
+    impl Event<TestWorld> for Spread {
+        fn execute(&mut self, _world: &mut TestWorld, ctx: &mut EventCtx<TestWorld>) -> EventResult {
+            let now = ctx.tick();
+            for _ in 0..3 {
+                let delay = (1..=3)
+                    .min_by_key(|delay| ctx.scheduler().get_pending_count_for_tick(now + delay))
+                    .unwrap();
+                ctx.scheduler().schedule(Box::new(Named("child")), delay);
+            }
+
+            EventResult::Continue
+        }
+    }
+
+    #[test]
+    fn test_get_pending_count_for_tick_spreads_load() {
+        let mut world = TestWorld { fired: vec![] };
+        let mut engine = Engine::build();
+        engine.schedule(Box::new(Named("busy")), 2);
+        engine.schedule(Box::new(Spread), 1);
+
+        engine.step_until(4, &mut world);
+
+        assert_eq!(
+            world.fired,
+            vec![(2, "busy"), (2, "child"), (3, "child"), (4, "child")]
+        );
+    }
+}
@@ -0,0 +1,94 @@
+use crate::CloneEvent;
+use crate::EventId;
+use crate::event_queue::QueueKey;
+use crate::scheduled_wrapper::{Repeat, ScheduledEvent};
+use std::collections::HashMap;
+
+/// the engine's scheduling state at one point in time, without the world.
+/// taken by `Engine::snapshot` and put back with `Engine::restore_snapshot`
+pub struct EngineSnapshot<W> {
+    pub(crate) current_tick: u64,
+    pub(crate) id_counter: u64,
+    pub(crate) total_events_executed: u64,
+    pub(crate) entries: Vec<SnapshotEntry<W>>,
+}
+
+impl<W> EngineSnapshot<W> {
+    pub fn current_tick(&self) -> u64 {
+        self.current_tick
+    }
+
+    pub fn queue_len(&self) -> usize {
+        self.entries.len()
+    }
+}
+
+// cloning copies every event again, so one snapshot can be restored any number of times
+impl<W: 'static> Clone for EngineSnapshot<W> {
+    fn clone(&self) -> Self {
+        Self {
+            current_tick: self.current_tick,
+            id_counter: self.id_counter,
+            total_events_executed: self.total_events_executed,
+            entries: self.entries.clone(),
+        }
+    }
+}
+
+// a pending event with everything `ScheduledEvent` carries
+pub(crate) struct SnapshotEntry<W> {
+    id: EventId,
+    event: Box<dyn CloneEvent<W>>,
+    metadata: HashMap<String, String>,
+    spawned_by: Option<EventId>,
+    spawn_depth: u32,
+    tag: Option<&'static str>,
+    priority: i32,
+    repeat: Option<Repeat>,
+    pub(crate) key: QueueKey,
+}
+
+impl<W: 'static> SnapshotEntry<W> {
+    // `None` if the event does not opt in through `Event::as_clone_event`
+    pub(crate) fn capture(item: &ScheduledEvent<W>, key: QueueKey) -> Option<Self> {
+        Some(Self {
+            id: item.id,
+            event: item.event.as_clone_event()?.clone_event(),
+            metadata: item.metadata.clone(),
+            spawned_by: item.spawned_by,
+            spawn_depth: item.spawn_depth,
+            tag: item.tag,
+            priority: item.priority,
+            repeat: item.repeat,
+            key,
+        })
+    }
+
+    pub(crate) fn into_scheduled(self) -> ScheduledEvent<W> {
+        let mut item = ScheduledEvent::new(self.id, self.event);
+        item.metadata = self.metadata;
+        item.spawned_by = self.spawned_by;
+        item.spawn_depth = self.spawn_depth;
+        item.tag = self.tag;
+        item.priority = self.priority;
+        item.repeat = self.repeat;
+
+        item
+    }
+}
+
+impl<W: 'static> Clone for SnapshotEntry<W> {
+    fn clone(&self) -> Self {
+        Self {
+            id: self.id,
+            event: self.event.clone_event(),
+            metadata: self.metadata.clone(),
+            spawned_by: self.spawned_by,
+            spawn_depth: self.spawn_depth,
+            tag: self.tag,
+            priority: self.priority,
+            repeat: self.repeat,
+            key: self.key,
+        }
+    }
+}
@@ -0,0 +1,189 @@
+use crate::Event;
+use crate::EventId;
+use crate::event_queue::QueueKey;
+use crate::scheduled_wrapper::{Repeat, ScheduledEvent};
+use serde::{Deserialize, Serialize};
+use std::any::Any;
+use std::collections::{HashMap, HashSet};
+use std::convert::Infallible;
+use std::sync::{Mutex, OnceLock};
+
+/// what `Engine::to_state` captures: the counters and every pending event as an `E`.
+/// `Box<dyn Event<W>>` cannot be serialized as is, so instead of a type registry every pending
+/// event is downcast to the one concrete type `E`, usually an enum over all of the simulation's
+/// events. `schedule_tagged` tags are saved as strings and interned again on restore, so each
+/// distinct tag is allocated once for the life of the process
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EngineState<E> {
+    pub current_tick: u64,
+    pub id_counter: u64,
+    pub total_events_executed: u64,
+    pub(crate) entries: Vec<StateEntry<E>>,
+}
+
+impl<E> EngineState<E> {
+    pub fn queue_len(&self) -> usize {
+        self.entries.len()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) struct StateEntry<E> {
+    id: EventId,
+    event: E,
+    metadata: HashMap<String, String>,
+    spawned_by: Option<EventId>,
+    spawn_depth: u32,
+    priority: i32,
+    repeat: Option<Repeat>,
+    // absent from states saved before tags were kept
+    #[serde(default)]
+    tag: Option<String>,
+    key: QueueKey,
+}
+
+impl<E> StateEntry<E> {
+    // `None` if the event is not an `E`
+    pub(crate) fn capture<W: 'static>(item: &ScheduledEvent<W>, key: QueueKey) -> Option<Self>
+    where
+        E: Event<W> + Clone,
+    {
+        let event: &dyn Any = &*item.event;
+
+        Some(Self::capture_as(item, key, event.downcast_ref::<E>()?.clone()))
+    }
+
+    // everything but the event itself comes from `item`
+    pub(crate) fn capture_as<W>(item: &ScheduledEvent<W>, key: QueueKey, event: E) -> Self {
+        Self {
+            id: item.id,
+            event,
+            metadata: item.metadata.clone(),
+            spawned_by: item.spawned_by,
+            spawn_depth: item.spawn_depth,
+            priority: item.priority,
+            repeat: item.repeat,
+            tag: item.tag.map(str::to_string),
+            key,
+        }
+    }
+
+    pub(crate) fn into_scheduled<W: 'static>(self) -> (ScheduledEvent<W>, QueueKey)
+    where
+        E: Event<W>,
+    {
+        match self.try_into_scheduled(|event| Ok::<_, Infallible>(Box::new(event))) {
+            Ok(entry) => entry,
+            Err(never) => match never {},
+        }
+    }
+
+    pub(crate) fn try_into_scheduled<W, Err>(
+        self,
+        rebuild: impl FnOnce(E) -> Result<Box<dyn Event<W>>, Err>,
+    ) -> Result<(ScheduledEvent<W>, QueueKey), Err> {
+        let mut item = ScheduledEvent::new(self.id, rebuild(self.event)?);
+        item.metadata = self.metadata;
+        item.spawned_by = self.spawned_by;
+        item.spawn_depth = self.spawn_depth;
+        item.priority = self.priority;
+        item.repeat = self.repeat;
+        item.tag = self.tag.as_deref().map(intern_tag);
+
+        Ok((item, self.key))
+    }
+}
+
+// tags are `&'static str` everywhere else, so a restored one has to live as long. leaking each
+// distinct tag once keeps repeated restores from growing memory
+fn intern_tag(tag: &str) -> &'static str {
+    static TAGS: OnceLock<Mutex<HashSet<&'static str>>> = OnceLock::new();
+
+    let mut tags = TAGS
+        .get_or_init(Default::default)
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    if let Some(&interned) = tags.get(tag) {
+        return interned;
+    }
+
+    let interned: &'static str = Box::leak(tag.to_string().into_boxed_str());
+    tags.insert(interned);
+    interned
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Engine, EventCtx, EventQuota, EventResult};
+
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+    enum Ledger {
+        Deposit(i64),
+        Withdraw(i64),
+    }
+
+    impl Event<i64> for Ledger {
+        fn execute(&mut self, balance: &mut i64, _ctx: &mut EventCtx<i64>) -> EventResult {
+            match *self {
+                Ledger::Deposit(amount) => *balance += amount,
+                Ledger::Withdraw(amount) => *balance -= amount,
+            }
+
+            EventResult::Continue
+        }
+    }
+
+    struct Untracked;
+
+    impl Event<i64> for Untracked {
+        fn execute(&mut self, _balance: &mut i64, _ctx: &mut EventCtx<i64>) -> EventResult {
+            EventResult::Continue
+        }
+    }
+
+    #[test]
+    fn test_engine_state_round_trips_through_json() {
+        let mut engine = Engine::build();
+        engine.schedule(Box::new(Ledger::Deposit(50)), 2);
+        engine.schedule_repeating(Box::new(Ledger::Withdraw(10)), 3, 3, Some(2));
+        engine.step(&mut 0);
+
+        let json = serde_json::to_string(&engine.to_state::<Ledger>().unwrap()).unwrap();
+        let state: EngineState<Ledger> = serde_json::from_str(&json).unwrap();
+        assert_eq!(state.current_tick, 1);
+        assert_eq!(state.queue_len(), 2);
+
+        let mut restored = Engine::build();
+        restored.restore_state(state);
+        let mut balance = 0;
+        restored.step_until(10, &mut balance);
+        assert_eq!(balance, 30);
+        assert_eq!(restored.schedule(Box::new(Ledger::Deposit(1)), 1).id(), EventId(3));
+
+        restored.schedule(Box::new(Untracked), 1);
+        assert!(restored.to_state::<Ledger>().is_none());
+    }
+
+    #[test]
+    fn test_tags_survive_a_round_trip() {
+        let mut engine = Engine::build();
+        engine.schedule_tagged(Box::new(Ledger::Deposit(5)), 1, "payroll");
+        engine.schedule_tagged(Box::new(Ledger::Deposit(7)), 1, "payroll");
+        engine.schedule_tagged(Box::new(Ledger::Deposit(100)), 2, "bonus");
+
+        let json = serde_json::to_string(&engine.to_state::<Ledger>().unwrap()).unwrap();
+        let quota = EventQuota::new().with_limit("payroll", 1);
+        let mut restored = Engine::build().with_event_quota(quota);
+        restored.restore_state(serde_json::from_str::<EngineState<Ledger>>(&json).unwrap());
+
+        let mut balance = 0;
+        restored.step(&mut balance);
+        assert_eq!(balance, 5);
+        assert_eq!(restored.cancel_by_tag("bonus"), 1);
+
+        restored.step_until(5, &mut balance);
+        assert_eq!(balance, 12);
+        assert!(std::ptr::eq(intern_tag("payroll"), intern_tag(&String::from("payroll"))));
+    }
+}
@@ -0,0 +1,184 @@
+use crate::{Engine, Event, StepReport};
+use std::sync::{Arc, Mutex, MutexGuard};
+
+type InboxEntry<W> = (Box<dyn Event<W> + Send>, u64);
+
+// the part of a `SyncEngine` other threads can touch: the tick events are scheduled against and
+// the events waiting to be moved into the engine
+struct EngineInner<W> {
+    current_tick: u64,
+    inbox: Vec<InboxEntry<W>>,
+}
+
+/// an `Engine` that accepts events from other threads. the engine itself stays on the thread
+/// that steps it (its events, plugins and hooks need not be `Send`); other threads schedule
+/// through a `SyncScheduler` from `scheduler`. `step` holds the shared lock for the whole tick,
+/// so an event scheduled concurrently lands either before or after it, never during
+pub struct SyncEngine<W> {
+    engine: Engine<W>,
+    inner: Arc<Mutex<EngineInner<W>>>,
+}
+
+impl<W: 'static> SyncEngine<W> {
+    pub fn new(engine: Engine<W>) -> Self {
+        let inner = EngineInner {
+            current_tick: engine.get_current_tick(),
+            inbox: Vec::new(),
+        };
+
+        Self {
+            engine,
+            inner: Arc::new(Mutex::new(inner)),
+        }
+    }
+
+    /// a handle for scheduling into this engine from any thread
+    pub fn scheduler(&self) -> SyncScheduler<W> {
+        SyncScheduler {
+            inner: Arc::clone(&self.inner),
+        }
+    }
+
+    pub fn schedule(&self, event: Box<dyn Event<W> + Send>, delay: u64) {
+        schedule_into(&self.inner, event, delay);
+    }
+
+    /// moves everything scheduled from other threads into the engine and runs one tick
+    pub fn step(&mut self, world: &mut W) -> StepReport {
+        let mut inner = lock(&self.inner);
+        flush(&mut self.engine, &mut inner);
+
+        let report = self.engine.step(world);
+        inner.current_tick = self.engine.get_current_tick();
+
+        report
+    }
+
+    /// the wrapped engine, with events scheduled from other threads moved in. stepping it directly
+    /// leaves `SyncScheduler::current_tick` behind until the next `step` or `engine_mut`, so
+    /// events scheduled meanwhile count from that older tick; any already overdue by then run on
+    /// the next step
+    pub fn engine_mut(&mut self) -> &mut Engine<W> {
+        let mut inner = lock(&self.inner);
+        flush(&mut self.engine, &mut inner);
+        drop(inner);
+
+        &mut self.engine
+    }
+
+    pub fn into_engine(mut self) -> Engine<W> {
+        self.engine_mut();
+
+        self.engine
+    }
+}
+
+/// schedules into a `SyncEngine` from any thread. delays count from the engine's tick at the
+/// time of the call, the same as `Engine::schedule`
+pub struct SyncScheduler<W> {
+    inner: Arc<Mutex<EngineInner<W>>>,
+}
+
+impl<W> SyncScheduler<W> {
+    pub fn schedule(&self, event: Box<dyn Event<W> + Send>, delay: u64) {
+        schedule_into(&self.inner, event, delay);
+    }
+
+    /// the tick of the last completed step
+    pub fn current_tick(&self) -> u64 {
+        lock(&self.inner).current_tick
+    }
+}
+
+impl<W> Clone for SyncScheduler<W> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: Arc::clone(&self.inner),
+        }
+    }
+}
+
+fn flush<W: 'static>(engine: &mut Engine<W>, inner: &mut EngineInner<W>) {
+    let now = engine.get_current_tick();
+    for (event, tick) in inner.inbox.drain(..) {
+        engine.schedule(event, tick.saturating_sub(now));
+    }
+    inner.current_tick = now;
+}
+
+fn schedule_into<W>(inner: &Mutex<EngineInner<W>>, event: Box<dyn Event<W> + Send>, delay: u64) {
+    let mut inner = lock(inner);
+    let tick = inner.current_tick.saturating_add(delay);
+    inner.inbox.push((event, tick));
+}
+
+// the inbox is only ever pushed to or drained, so it is consistent even after a panic elsewhere
+fn lock<W>(inner: &Mutex<EngineInner<W>>) -> MutexGuard<'_, EngineInner<W>> {
+    inner.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{EventCtx, EventResult};
+    use std::thread;
+
+    struct Deposit {
+        amount: u64,
+    }
+
+    impl Event<Vec<(u64, u64)>> for Deposit {
+        fn execute(&mut self, log: &mut Vec<(u64, u64)>, ctx: &mut EventCtx<Vec<(u64, u64)>>) -> EventResult {
+            log.push((ctx.tick(), self.amount));
+            EventResult::Continue
+        }
+    }
+
+    #[test]
+    fn test_events_scheduled_from_other_threads_run_on_step() {
+        let mut engine = SyncEngine::new(Engine::build());
+        let mut log = Vec::new();
+        engine.step(&mut log);
+
+        thread::scope(|scope| {
+            for amount in 0..4 {
+                let scheduler = engine.scheduler();
+                scope.spawn(move || scheduler.schedule(Box::new(Deposit { amount }), amount + 1));
+            }
+        });
+        engine.schedule(Box::new(Deposit { amount: 10 }), 2);
+
+        while engine.step(&mut log).queue_len_after > 0 {}
+
+        log.sort();
+        assert_eq!(log, vec![(2, 0), (3, 1), (3, 10), (4, 2), (5, 3)]);
+        assert_eq!(engine.scheduler().current_tick(), 5);
+    }
+
+    #[test]
+    fn test_stepping_through_engine_mut_does_not_lose_events() {
+        let mut engine = SyncEngine::new(Engine::build());
+        let scheduler = engine.scheduler();
+        let mut log = Vec::new();
+
+        scheduler.schedule(Box::new(Deposit { amount: 1 }), 2);
+        engine.engine_mut().step_until(5, &mut log);
+        scheduler.schedule(Box::new(Deposit { amount: 2 }), 1);
+        assert_eq!(scheduler.current_tick(), 0);
+
+        // the second deposit was due at tick 1, which has long passed
+        engine.step(&mut log);
+        assert_eq!(log, vec![(2, 1), (6, 2)]);
+        assert_eq!(scheduler.current_tick(), 6);
+
+        scheduler.schedule(Box::new(Deposit { amount: 3 }), 1);
+        engine.step(&mut log);
+        assert_eq!(log, vec![(2, 1), (6, 2), (7, 3)]);
+    }
+
+    #[test]
+    fn test_scheduler_is_send_and_sync() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<SyncScheduler<Vec<(u64, u64)>>>();
+    }
+}
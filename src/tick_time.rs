@@ -0,0 +1,187 @@
+use std::fmt;
+use std::ops::Add;
+
+/// what engine time is measured in. `u64` counts discrete ticks and is the default everywhere;
+/// any other ordered type works as long as a delay of the same type can be added to it, e.g. a
+/// newtype over `f64` seconds ordered with `f64::total_cmp`
+pub trait TickTime: Copy + Ord + Add<Output = Self> + fmt::Debug + fmt::Display + 'static {
+    /// where time starts, and the delay for "later this tick"
+    const ZERO: Self;
+    /// one tick later; how far an `EventQuota` pushes back events it holds over
+    const ONE: Self;
+    /// where `OverflowPolicy::Saturate` stops
+    const MAX: Self;
+
+    /// `self + stride` for clocks, which may wrap around instead of panicking. the engine
+    /// treats a time earlier than the previous one as an overflow
+    fn wrapping_add(self, stride: Self) -> Self {
+        self + stride
+    }
+
+    /// `self + delay`, or `None` past `MAX`. the engine applies its `OverflowPolicy` to `None`
+    fn checked_add(self, delay: Self) -> Option<Self> {
+        Some(self + delay)
+    }
+
+    /// whole ticks strictly between `self` and the later `time`, which
+    /// `Engine::advance_to_next_event` counts as idle. continuous time has none
+    fn ticks_between(self, _time: Self) -> u64 {
+        0
+    }
+}
+
+impl TickTime for u64 {
+    const ZERO: Self = 0;
+    const ONE: Self = 1;
+    const MAX: Self = u64::MAX;
+
+    fn wrapping_add(self, stride: Self) -> Self {
+        u64::wrapping_add(self, stride)
+    }
+
+    fn checked_add(self, delay: Self) -> Option<Self> {
+        u64::checked_add(self, delay)
+    }
+
+    fn ticks_between(self, time: Self) -> u64 {
+        time.saturating_sub(self).saturating_sub(1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        ChannelProgressReporter, Engine, Event, EventCtx, EventResult, MonotonicClock, RecurringEvent,
+        TimelineEntry, TimelinePlugin,
+    };
+    use std::cmp::Ordering;
+    use std::sync::mpsc;
+
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    struct Seconds(f64);
+
+    impl Eq for Seconds {}
+
+    impl PartialOrd for Seconds {
+        fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+            Some(self.cmp(other))
+        }
+    }
+
+    impl Ord for Seconds {
+        fn cmp(&self, other: &Self) -> Ordering {
+            self.0.total_cmp(&other.0)
+        }
+    }
+
+    impl Add for Seconds {
+        type Output = Self;
+
+        fn add(self, other: Self) -> Self {
+            Seconds(self.0 + other.0)
+        }
+    }
+
+    impl fmt::Display for Seconds {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "{}s", self.0)
+        }
+    }
+
+    impl TickTime for Seconds {
+        const ZERO: Self = Seconds(0.0);
+        const ONE: Self = Seconds(1.0);
+        const MAX: Self = Seconds(f64::MAX);
+    }
+
+    struct Ping {
+        every: f64,
+    }
+
+    impl Event<Vec<f64>, Seconds> for Ping {
+        fn execute(&mut self, log: &mut Vec<f64>, ctx: &mut EventCtx<Vec<f64>, Seconds>) -> EventResult {
+            log.push(ctx.tick().0);
+            if log.len() < 3 {
+                ctx.scheduler().schedule(Box::new(Ping { every: self.every }), Seconds(self.every));
+            }
+
+            EventResult::Continue
+        }
+    }
+
+    struct Mark;
+
+    impl Event<Vec<f64>, Seconds> for Mark {
+        fn execute(&mut self, log: &mut Vec<f64>, ctx: &mut EventCtx<Vec<f64>, Seconds>) -> EventResult {
+            log.push(ctx.tick().0);
+
+            EventResult::Continue
+        }
+    }
+
+    fn seconds_engine() -> Engine<Vec<f64>, Seconds> {
+        let clock = MonotonicClock::with_step(Seconds(0.0), Seconds(0.5));
+        Engine::build_with_clock(Box::new(clock))
+    }
+
+    #[test]
+    fn test_continuous_time_fires_at_exact_times() {
+        let mut engine = seconds_engine();
+        engine.schedule(Box::new(Ping { every: 0.375 }), Seconds(0.25));
+
+        let mut log = Vec::new();
+        assert_eq!(engine.run_until_idle(&mut log), Ok(Seconds(1.0)));
+        assert_eq!(log, vec![0.25, 0.625, 1.0]);
+        assert_eq!(engine.total_ticks_without_events(), 0);
+    }
+
+    #[test]
+    fn test_continuous_time_steps_by_the_clock() {
+        let mut engine = seconds_engine();
+        engine.schedule(Box::new(Ping { every: 1.0 }), Seconds(0.75));
+
+        let mut log = Vec::new();
+        assert_eq!(engine.step(&mut log).executed, 0);
+        assert_eq!(engine.step(&mut log).tick, Seconds(1.0));
+        assert_eq!(log, vec![1.0]);
+    }
+
+    #[test]
+    fn test_continuous_time_queue_introspection_and_progress() {
+        let mut engine = seconds_engine();
+        let first = engine.schedule(Box::new(Ping { every: 1.0 }), Seconds(0.75));
+        assert_eq!(engine.queue_iter().collect::<Vec<_>>(), vec![(Seconds(0.75), first.id())]);
+
+        let mut log = Vec::new();
+        assert_eq!(engine.step_n(2, &mut log), 1);
+
+        let (tx, rx) = mpsc::channel();
+        engine.run_with_progress(&mut log, Seconds(2.0), &mut ChannelProgressReporter::new(tx));
+        assert_eq!(log, vec![1.0, 2.0]);
+        assert_eq!(rx.try_iter().last(), Some((Seconds(2.0), Seconds(2.0))));
+    }
+
+    #[test]
+    fn test_continuous_time_helpers() {
+        let mut engine = seconds_engine().with_inspector_plugin(TimelinePlugin::new());
+        let recurring = RecurringEvent::new(Box::new(Mark), Seconds(0.5)).stop_after(3);
+        engine.schedule(Box::new(recurring), Seconds(0.5));
+        engine.enqueue_world_action(Seconds(0.75), |log: &mut Vec<f64>| log.push(-1.0));
+
+        let mut log = Vec::new();
+        assert_eq!(engine.run_until_idle(&mut log), Ok(Seconds(1.5)));
+        assert_eq!(log, vec![0.5, -1.0, 1.0, 1.5]);
+
+        let timeline = engine.inspector_plugin::<TimelinePlugin<Seconds>>().unwrap();
+        let executed: Vec<f64> = timeline
+            .entries()
+            .iter()
+            .filter_map(|entry| match entry {
+                TimelineEntry::Executed { tick, .. } => Some(tick.0),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(executed, vec![0.5, 0.75, 1.0, 1.5]);
+    }
+}